@@ -0,0 +1,829 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::console::Console;
+use crate::dsa::heap::{self, HeapType};
+use crate::exercises::find_median::{self, running, selection};
+use crate::exercises::pig_latin::{self, decode, stream};
+use crate::exercises::statistics;
+use crate::exercises::word_games::{Pipeline, Transform};
+use crate::games::fibonacci::{self, modular};
+use crate::games::songs::{self, karaoke};
+use crate::games::{christmas_lyrics, temp_converter, unit_converter};
+use crate::random;
+
+const USAGE: &str = "\
+Usage: guessing_game [--seed <seed>] [COMMAND] [ARGS]
+
+Without a command the interactive menu is started.
+
+Options:
+  --seed <seed>                 Seed the random number generator so a session can be
+                                replayed. Must come before the command.
+                                GUESSING_GAME_SEED is used when not given.
+
+Commands:
+  fib <terms>                   Print the first <terms> Fibonacci numbers
+  fib --nth <n> [--digits | --first <k> | --last <k>]
+                                Print F(n) for n up to 1,000,000, its number of digits,
+                                or only its first or last <k> digits
+  fib --nth <n> --mod <m>       Print F(n) mod m, where n may have hundreds of digits
+  convert <temperature> [--from <scale>] [--to <scale>]
+                                Convert a temperature such as 100C or \"100C to K\" between
+                                Celsius, Fahrenheit, Kelvin, Rankine, Delisle, Newton,
+                                Réaumur and Rømer. Without a target, show every scale
+  units <conversion> [--precision <decimals>]
+                                Convert between units, e.g. \"100 km/h to mph\"
+  lyrics [--song <name|file>] [--verses <n|first..last>] [--karaoke] [--tempo <lpm>]
+                                Print a cumulative song: twelve-days (the default),
+                                house-that-jack-built, old-macdonald, or a .toml or
+                                .json song file. --karaoke plays it line by line at
+                                --tempo lines per minute (90 by default)
+  lyrics --stats                Gift totals and word counts for the Twelve Days
+  lyrics [--song <name|file>] --export <file> [--format txt|md|html]
+                                Save a song as text, Markdown or HTML
+  median <list> [--algorithm sort|quickselect|mo3|mom]
+                                Median of a comma separated list of integers
+  median --benchmark <size>     Time the selection algorithms on random, sorted and
+                                adversarial inputs of <size> values
+  running-median [--window <size>] [<file>]
+                                Median after each integer read from <file> or standard
+                                input, over the last <size> values if given
+  stats <numbers>... [--percentile <p>]
+                                Summary statistics of integers or decimals, or only
+                                their <p>th percentile
+  piglatin [--decode] <text>... Translate text to Pig Latin, or back to English
+  piglatin [--decode] --file <input> [--output <file>]
+                                Translate a file, or standard input if <input> is -,
+                                line by line
+  transform <steps> [--decode] <text>...
+                                Encode text with transformations separated by |, such
+                                as \"pig-latin:way | rot13\", or decode it
+  heapsort <list> [--desc]      Sort a comma separated list of integers with a heap
+  help                          Show this message";
+
+/// Why a command could not be run.
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+    /// The command line itself is malformed.
+    Usage(String),
+    /// The command was understood but its input is not valid.
+    InvalidInput(String),
+}
+
+impl CliError {
+    /// Process exit code to report for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::InvalidInput(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}\n\n{}", message, USAGE),
+            CliError::InvalidInput(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Seed the shared random number generator from a leading `--seed` or, failing that, from the
+/// environment. Returns the remaining arguments. Without either, the random seed is printed to
+/// stderr so the session can still be replayed.
+pub fn apply_seed(args: &[String]) -> Result<Vec<String>, CliError> {
+    let leading = match args.first() {
+        Some(arg) if arg == "--seed" => 2,
+        Some(arg) if arg.starts_with("--seed=") => 1,
+        _ => 0,
+    };
+    let leading = leading.min(args.len());
+    let (seed, _) = take_option(&args[..leading], "--seed")?;
+    let args = args[leading..].to_vec();
+    let seed = match seed {
+        Some(seed) => Some(random::parse_seed(&seed).map_err(CliError::Usage)?),
+        None => random::seed_from_env().map_err(|message| {
+            CliError::InvalidInput(format!("{}: {}", random::SEED_ENV_VAR, message))
+        })?,
+    };
+    match seed {
+        Some(seed) => random::seed(seed),
+        None => eprintln!("Using seed {}", random::current_seed()),
+    }
+    Ok(args)
+}
+
+/// Run the non-interactive command named by `args[0]`, writing results to `console`.
+pub fn run(args: &[String], console: &mut dyn Console) -> Result<(), CliError> {
+    let Some((command, args)) = args.split_first() else {
+        return Err(CliError::Usage("No command given".to_string()));
+    };
+
+    match command.as_str() {
+        "fib" => {
+            let (nth, args) = take_option(args, "--nth")?;
+            let Some(nth) = nth else {
+                let [terms] = expect_args(command, &args, ["<terms>"])?;
+                let terms = fibonacci::parse_terms(terms).map_err(CliError::InvalidInput)?;
+                fibonacci::print_terms(console, terms);
+                return Ok(());
+            };
+            let (modulus, args) = take_option(&args, "--mod")?;
+            if let Some(modulus) = modulus {
+                if !args.is_empty() {
+                    return Err(CliError::Usage(
+                        "Usage: guessing_game fib --nth <n> --mod <m>".to_string(),
+                    ));
+                }
+                let modulus = modular::parse_modulus(&modulus).map_err(CliError::Usage)?;
+                let n = modular::parse_big_index(&nth).map_err(CliError::InvalidInput)?;
+                writeln!(
+                    console,
+                    "F({}) mod {} = {}",
+                    n,
+                    modulus,
+                    modular::fibonacci_mod(&n, modulus)
+                );
+                return Ok(());
+            }
+            let (first, args) = take_option(&args, "--first")?;
+            let (last, args) = take_option(&args, "--last")?;
+            let digit_count = args.iter().any(|arg| arg == "--digits");
+            let args: Vec<&String> = args.iter().filter(|arg| *arg != "--digits").collect();
+            let parse_count = |count: String| {
+                count.parse::<usize>().map_err(|_| {
+                    CliError::Usage(format!("\"{}\" is not a valid digit count", count))
+                })
+            };
+            let output = match (digit_count, first, last) {
+                (false, None, None) => fibonacci::NthOutput::Full,
+                (true, None, None) => fibonacci::NthOutput::DigitCount,
+                (false, Some(count), None) => fibonacci::NthOutput::First(parse_count(count)?),
+                (false, None, Some(count)) => fibonacci::NthOutput::Last(parse_count(count)?),
+                _ => {
+                    return Err(CliError::Usage(
+                        "Use only one of --digits, --first and --last".to_string(),
+                    ))
+                }
+            };
+            if !args.is_empty() {
+                return Err(CliError::Usage(
+                    "Usage: guessing_game fib --nth <n> [--digits | --first <k> | --last <k>]"
+                        .to_string(),
+                ));
+            }
+            let n = fibonacci::parse_nth(&nth).map_err(CliError::InvalidInput)?;
+            let description = fibonacci::describe_nth(n, output).map_err(CliError::InvalidInput)?;
+            writeln!(console, "{}", description);
+        }
+        "convert" => {
+            let (from, args) = take_option(args, "--from")?;
+            let (to, args) = take_option(&args, "--to")?;
+            if args.is_empty() {
+                return Err(CliError::Usage(
+                    "Usage: guessing_game convert <temperature> [--from <scale>] [--to <scale>]"
+                        .to_string(),
+                ));
+            }
+            let text = args.join(" ");
+            let parse_scale = |scale: Option<String>| match scale {
+                Some(scale) => temp_converter::Scale::parse(&scale)
+                    .map(Some)
+                    .ok_or_else(|| {
+                        CliError::InvalidInput(format!("Unknown temperature scale \"{}\"", scale))
+                    }),
+                None => Ok(None),
+            };
+            let from = parse_scale(from)?;
+            let request = match parse_scale(to)? {
+                Some(to) => {
+                    // A bare number converts from the "other" everyday scale, as it always has.
+                    let default = from.unwrap_or(match to {
+                        temp_converter::Scale::Celsius => temp_converter::Scale::Fahrenheit,
+                        _ => temp_converter::Scale::Celsius,
+                    });
+                    temp_converter::Temperature::parse(&text, Some(default))
+                        .map(|temperature| temp_converter::Request::Convert(temperature, to))
+                }
+                None if from.is_some() => temp_converter::Temperature::parse(&text, from)
+                    .map(temp_converter::Request::Table),
+                None => temp_converter::parse_request(&text),
+            }
+            .map_err(CliError::InvalidInput)?;
+            match request {
+                temp_converter::Request::Convert(temperature, to) => {
+                    writeln!(
+                        console,
+                        "{}",
+                        temp_converter::format_conversion(&temperature, to)
+                    )
+                }
+                temp_converter::Request::Table(temperature) => {
+                    writeln!(console, "{}", temp_converter::format_table(&temperature))
+                }
+            }
+        }
+        "units" => {
+            let (precision, args) = take_option(args, "--precision")?;
+            let precision = match precision {
+                Some(precision) => {
+                    unit_converter::parse_precision(&precision).map_err(CliError::Usage)?
+                }
+                None => unit_converter::DEFAULT_PRECISION,
+            };
+            if args.is_empty() {
+                return Err(CliError::Usage(
+                    "Usage: guessing_game units <conversion> [--precision <decimals>]".to_string(),
+                ));
+            }
+            let result = unit_converter::convert(&args.join(" "), precision)
+                .map_err(CliError::InvalidInput)?;
+            writeln!(console, "{}", result);
+        }
+        "lyrics" => {
+            let (song, args) = take_option(args, "--song")?;
+            let (verses, args) = take_option(&args, "--verses")?;
+            let (tempo, args) = take_option(&args, "--tempo")?;
+            let (export, args) = take_option(&args, "--export")?;
+            let (format, args) = take_option(&args, "--format")?;
+            let (karaoke, args) = take_flag(&args, "--karaoke");
+            let (stats, args) = take_flag(&args, "--stats");
+            expect_args(command, &args, [])?;
+            if stats {
+                if song.is_some() {
+                    return Err(CliError::Usage(
+                        "--stats only applies to the Twelve Days of Christmas".to_string(),
+                    ));
+                }
+                christmas_lyrics::print_statistics(console, &christmas_lyrics::statistics());
+                return Ok(());
+            }
+            let song = songs::find(song.as_deref().unwrap_or("twelve-days"))
+                .map_err(CliError::InvalidInput)?;
+            if let Some(path) = export {
+                let format = match format {
+                    Some(format) => Some(
+                        christmas_lyrics::ExportFormat::parse(&format).ok_or_else(|| {
+                            CliError::Usage(format!("Unknown export format \"{}\"", format))
+                        })?,
+                    ),
+                    None => None,
+                };
+                christmas_lyrics::export(&song, Path::new(&path), format)
+                    .map_err(CliError::InvalidInput)?;
+                writeln!(console, "Saved the lyrics to {}", path);
+                return Ok(());
+            }
+            let verses = songs::parse_verses(verses.as_deref().unwrap_or(""), song.verse_count())
+                .map_err(CliError::InvalidInput)?;
+            if karaoke || tempo.is_some() {
+                let tempo = karaoke::parse_tempo(tempo.as_deref().unwrap_or(""))
+                    .map_err(CliError::Usage)?;
+                karaoke::sing(console, &song, verses, tempo);
+            } else {
+                songs::print_verses(console, &song, verses);
+            }
+        }
+        "median" => {
+            let (benchmark, args) = take_option(args, "--benchmark")?;
+            let (algorithm, args) = take_option(&args, "--algorithm")?;
+            if let Some(size) = benchmark {
+                expect_args(command, &args, [])?;
+                let size = selection::parse_size(&size).map_err(CliError::Usage)?;
+                let timings = selection::benchmark(size);
+                selection::print_benchmark(console, size, &timings);
+                return Ok(());
+            }
+            let algorithm = match algorithm {
+                Some(name) => selection::Algorithm::parse(&name).ok_or_else(|| {
+                    CliError::Usage(format!("Unknown selection algorithm \"{}\"", name))
+                })?,
+                None => selection::Algorithm::Sort,
+            };
+            let [list] = expect_args(command, &args, ["<list>"])?;
+            let arr = parse_list(list)?;
+            let median = selection::median(&arr, algorithm).expect("the list is not empty");
+            writeln!(console, "{}", median);
+        }
+        "running-median" => {
+            let (window, args) = take_option(args, "--window")?;
+            let window =
+                running::parse_window(window.as_deref().unwrap_or("")).map_err(CliError::Usage)?;
+            let mut median = match window {
+                Some(size) => running::RunningMedian::with_window(size),
+                None => running::RunningMedian::new(),
+            };
+            match args.as_slice() {
+                [] => {
+                    // Numbers are read line by line from standard input until it ends
+                    while let Some(line) = console.read_line() {
+                        running::feed(console, &mut median, &line)
+                            .map_err(CliError::InvalidInput)?;
+                    }
+                }
+                [path] if !path.starts_with("--") => {
+                    running::feed_file(console, &mut median, path)
+                        .map_err(CliError::InvalidInput)?;
+                }
+                _ => {
+                    return Err(CliError::Usage(
+                        "Usage: guessing_game running-median [--window <size>] [<file>]"
+                            .to_string(),
+                    ))
+                }
+            }
+        }
+        "stats" => {
+            let (percentile, args) = take_option(args, "--percentile")?;
+            if args.is_empty() {
+                return Err(CliError::Usage(
+                    "Usage: guessing_game stats <numbers>... [--percentile <p>]".to_string(),
+                ));
+            }
+            let input = args.join(" ");
+            match percentile {
+                Some(p) => {
+                    let values =
+                        statistics::parse_floats(&input).map_err(CliError::InvalidInput)?;
+                    let p = p
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|p| (0.0..=100.0).contains(p))
+                        .ok_or_else(|| {
+                            CliError::Usage(format!("\"{}\" is not a percentile from 0 to 100", p))
+                        })?;
+                    let value = statistics::percentile(&values, p)
+                        .ok_or_else(|| CliError::InvalidInput("No numbers given".to_string()))?;
+                    writeln!(console, "{}", value);
+                }
+                None => {
+                    statistics::print_report(console, &input).map_err(CliError::InvalidInput)?
+                }
+            }
+        }
+        "piglatin" => {
+            let (decode, args) = take_flag(args, "--decode");
+            let (file, args) = take_option(&args, "--file")?;
+            let (output, args) = take_option(&args, "--output")?;
+            if let Some(file) = file {
+                if !args.is_empty() {
+                    return Err(CliError::Usage(
+                        "Give either <text> or --file, not both".to_string(),
+                    ));
+                }
+                let words = decode::WordList::english();
+                let convert = |line: &str| {
+                    if decode {
+                        decode::decode(line, Some(&words)).text
+                    } else {
+                        pig_latin::translate(line)
+                    }
+                };
+                stream::translate_file(console, &file, output.as_deref(), convert)
+                    .map_err(CliError::InvalidInput)?;
+                return Ok(());
+            }
+            if output.is_some() {
+                return Err(CliError::Usage("--output requires --file".to_string()));
+            }
+            if args.is_empty() {
+                return Err(CliError::Usage("piglatin requires <text>".to_string()));
+            }
+            if decode {
+                let words = decode::WordList::english();
+                decode::print_decoded(console, &decode::decode(&args.join(" "), Some(&words)));
+            } else {
+                writeln!(console, "{}", pig_latin::translate(&args.join(" ")));
+            }
+        }
+        "transform" => {
+            let (decode, args) = take_flag(args, "--decode");
+            let [steps, text @ ..] = args.as_slice() else {
+                return Err(CliError::Usage("transform requires <steps>".to_string()));
+            };
+            if text.is_empty() {
+                return Err(CliError::Usage("transform requires <text>".to_string()));
+            }
+            let pipeline = Pipeline::parse(steps).map_err(CliError::Usage)?;
+            let text = text.join(" ");
+            if decode {
+                writeln!(console, "{}", pipeline.decode(&text));
+            } else {
+                writeln!(console, "{}", pipeline.encode(&text));
+            }
+        }
+        "heapsort" => {
+            let (descending, args) = take_flag(args, "--desc");
+            let [list] = expect_args(command, &args, ["<list>"])?;
+            let heap_type = if descending {
+                HeapType::MaxHeap
+            } else {
+                HeapType::MinHeap
+            };
+            let sorted = heap::heap_sort(parse_list(list)?, heap_type);
+            let sorted: Vec<String> = sorted.iter().map(|value| value.to_string()).collect();
+            writeln!(console, "{}", sorted.join(","));
+        }
+        "help" | "--help" | "-h" => writeln!(console, "{}", USAGE),
+        _ => return Err(CliError::Usage(format!("Unknown command \"{}\"", command))),
+    }
+    Ok(())
+}
+
+/// Check that exactly the positional arguments named in `names` were given.
+fn expect_args<'a, const N: usize>(
+    command: &str,
+    args: &'a [String],
+    names: [&str; N],
+) -> Result<[&'a str; N], CliError> {
+    if args.len() != N || args.iter().any(|arg| arg.starts_with("--")) {
+        return Err(CliError::Usage(format!(
+            "Usage: guessing_game {} {}",
+            command,
+            names.join(" ")
+        )));
+    }
+    Ok(std::array::from_fn(|i| args[i].as_str()))
+}
+
+/// Remove `--name <value>` (or `--name=<value>`) from `args`, returning the value if present.
+fn take_option(args: &[String], name: &str) -> Result<(Option<String>, Vec<String>), CliError> {
+    let mut value = None;
+    let mut rest = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == name {
+            match iter.next() {
+                Some(next) => value = Some(next.clone()),
+                None => return Err(CliError::Usage(format!("{} requires a value", name))),
+            }
+        } else if let Some(inline) = arg.strip_prefix(name).and_then(|s| s.strip_prefix('=')) {
+            value = Some(inline.to_string());
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    Ok((value, rest))
+}
+
+/// Remove every `name` flag from `args`, returning whether it was present.
+fn take_flag(args: &[String], name: &str) -> (bool, Vec<String>) {
+    let rest: Vec<String> = args.iter().filter(|arg| *arg != name).cloned().collect();
+    (rest.len() != args.len(), rest)
+}
+
+fn parse_list(list: &str) -> Result<Vec<i32>, CliError> {
+    match find_median::parse_list(list) {
+        Ok(arr) if !arr.is_empty() => Ok(arr),
+        _ => Err(CliError::InvalidInput(format!(
+            "\"{}\" is not a valid list of integers",
+            list
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+    use std::fs;
+
+    fn run_command(args: &[&str]) -> (Result<(), CliError>, String) {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let mut console = ScriptedConsole::default();
+        let result = run(&args, &mut console);
+        (result, console.output().to_string())
+    }
+
+    #[test]
+    fn test_fib() {
+        assert_eq!(
+            run_command(&["fib", "6"]),
+            (Ok(()), "0 1 1 2 3 5 \n".to_string())
+        );
+        assert_eq!(run_command(&["fib", "10001"]).0.unwrap_err().exit_code(), 1);
+        assert_eq!(run_command(&["fib"]).0.unwrap_err().exit_code(), 2);
+    }
+
+    #[test]
+    fn test_fib_nth() {
+        assert_eq!(
+            run_command(&["fib", "--nth", "100"]),
+            (Ok(()), "F(100) = 354224848179261915075\n".to_string())
+        );
+        assert_eq!(
+            run_command(&["fib", "--nth=10000", "--digits"]),
+            (Ok(()), "F(10000) has 2090 digits\n".to_string())
+        );
+        assert_eq!(
+            run_command(&["fib", "--nth", "10000", "--last", "6"]),
+            (Ok(()), "Last 6 digits of F(10000): 366875\n".to_string())
+        );
+        assert_eq!(
+            run_command(&["fib", "--nth", "5", "--digits", "--first", "2"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            2
+        );
+        let googol = format!("1{}", "0".repeat(100));
+        assert_eq!(
+            run_command(&["fib", "--nth", &googol, "--mod", "1000000007"]),
+            (
+                Ok(()),
+                format!("F({}) mod 1000000007 = 175077019\n", googol)
+            )
+        );
+        assert_eq!(
+            run_command(&["fib", "--nth", "10", "--mod", "0"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            2
+        );
+        assert_eq!(
+            run_command(&["fib", "--nth", "2000000"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_convert() {
+        let expected = "100.00 °C = 212.00 °F\n".to_string();
+        assert_eq!(
+            run_command(&["convert", "100", "--to", "f"]),
+            (Ok(()), expected.clone())
+        );
+        assert_eq!(
+            run_command(&["convert", "--to=F", "100"]),
+            (Ok(()), expected.clone())
+        );
+        assert_eq!(
+            run_command(&["convert", "100C", "to", "F"]),
+            (Ok(()), expected)
+        );
+        assert_eq!(
+            run_command(&["convert", "212", "--from", "f", "--to", "k"]),
+            (Ok(()), "212.00 °F = 373.15 K\n".to_string())
+        );
+        assert_eq!(run_command(&["convert", "0K"]).1.lines().count(), 8);
+        assert_eq!(run_command(&["convert"]).0.unwrap_err().exit_code(), 2);
+        assert_eq!(
+            run_command(&["convert", "100", "--to", "x"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            1
+        );
+        assert_eq!(
+            run_command(&["convert", "-1K", "--to", "c"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_units() {
+        assert_eq!(
+            run_command(&["units", "100", "km/h", "to", "mph", "--precision", "1"]),
+            (Ok(()), "100.0 km/h = 62.1 mph\n".to_string())
+        );
+        assert_eq!(
+            run_command(&["units", "1 m to kg"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            1
+        );
+        assert_eq!(
+            run_command(&["units", "1 m to cm", "--precision", "x"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_median_and_piglatin() {
+        assert_eq!(
+            run_command(&["median", "3,1,2"]),
+            (Ok(()), "2\n".to_string())
+        );
+        assert_eq!(
+            run_command(&["median", "3,a"]).0.unwrap_err().exit_code(),
+            1
+        );
+        assert_eq!(
+            run_command(&["median", "4,1,3,2", "--algorithm", "mom"]),
+            (Ok(()), "2.5\n".to_string())
+        );
+        assert_eq!(
+            run_command(&["median", "1", "--algorithm", "bogo"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            2
+        );
+        assert!(run_command(&["median", "--benchmark", "100"])
+            .1
+            .starts_with("Median of 100 values"));
+        assert_eq!(
+            run_command(&["piglatin", "hello", "world"]),
+            (Ok(()), "ellohay orldway\n".to_string())
+        );
+        assert_eq!(
+            run_command(&["piglatin", "--decode", "Ellohay", "oxfay"]),
+            (
+                Ok(()),
+                "English: Hello fox\n  \"oxfay\" could be: fox, xfo\n".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_piglatin_file() {
+        let dir = std::env::temp_dir().join(format!("cli_piglatin_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input.txt");
+        let output = dir.join("output.txt");
+        fs::write(&input, "Hello, world!\n\n  again\n").unwrap();
+        let (input, output) = (input.to_str().unwrap(), output.to_str().unwrap());
+
+        assert_eq!(
+            run_command(&["piglatin", "--file", input]),
+            (Ok(()), "Ellohay, orldway!\n\n  againhay\n".to_string())
+        );
+        assert_eq!(
+            run_command(&["piglatin", "--file", input, "--output", output]),
+            (Ok(()), String::new())
+        );
+        assert_eq!(
+            run_command(&["piglatin", "--decode", "--file", output]),
+            (Ok(()), "Hello, world!\n\n  again\n".to_string())
+        );
+        assert_eq!(
+            run_command(&["piglatin", "--output", output, "hi"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            2
+        );
+        assert_eq!(
+            run_command(&["piglatin", "--file", "/no/such/file"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            1
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_transform() {
+        assert_eq!(
+            run_command(&["transform", "pig-latin:way|rot13", "Hello", "apple"]),
+            (Ok(()), "Ryybunl nccyrjnl\n".to_string())
+        );
+        assert_eq!(
+            run_command(&["transform", "--decode", "vigenere:lemon", "Lxfopv ef rnhr"]),
+            (Ok(()), "Attack at dawn\n".to_string())
+        );
+        for args in [&["transform", "rot13"][..], &["transform", "morse", "hi"]] {
+            assert_eq!(run_command(args).0.unwrap_err().exit_code(), 2);
+        }
+    }
+
+    #[test]
+    fn test_running_median_reads_input() {
+        let args: Vec<String> = ["running-median", "--window", "2"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let mut console = ScriptedConsole::new(["1 3", "5"]);
+        assert_eq!(run(&args, &mut console), Ok(()));
+        assert_eq!(
+            console.output(),
+            "1 -> median 1\n3 -> median 2\n5 -> median 4\n"
+        );
+        assert_eq!(
+            run_command(&["running-median", "/no/such/file"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let (result, output) = run_command(&["stats", "1,2,3", "4"]);
+        assert!(result.is_ok());
+        assert!(output.contains("Median:             2.5\n"));
+        assert_eq!(
+            run_command(&["stats", "1 2 3 4 5", "--percentile", "75"]),
+            (Ok(()), "4\n".to_string())
+        );
+        assert_eq!(run_command(&["stats", "1,x"]).0.unwrap_err().exit_code(), 1);
+        assert_eq!(
+            run_command(&["stats", "1", "--percentile", "200"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_heapsort_and_lyrics() {
+        assert_eq!(
+            run_command(&["heapsort", "3,1,2"]),
+            (Ok(()), "1,2,3\n".to_string())
+        );
+        assert_eq!(
+            run_command(&["heapsort", "--desc", "3,1,2"]),
+            (Ok(()), "3,2,1\n".to_string())
+        );
+        assert!(run_command(&["lyrics"])
+            .1
+            .contains("twelfth day of Christmas"));
+        assert_eq!(
+            run_command(&["lyrics", "--song", "house-that-jack-built", "--verses", "2"]),
+            (
+                Ok(()),
+                "This is the malt\nThat lay in the house that Jack built.\n\n".to_string()
+            )
+        );
+        let (result, output) = run_command(&["lyrics", "--stats"]);
+        assert!(result.is_ok());
+        assert!(output.contains("Total gifts: 364\n"));
+        // Without a terminal, karaoke falls back to plain output
+        assert_eq!(
+            run_command(&["lyrics", "--karaoke", "--verses", "1..2"]),
+            run_command(&["lyrics", "--verses", "1..2"])
+        );
+        assert_eq!(
+            run_command(&["lyrics", "--tempo", "1"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            2
+        );
+        assert_eq!(
+            run_command(&["lyrics", "--verses", "13"])
+                .0
+                .unwrap_err()
+                .exit_code(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_apply_seed() {
+        let args: Vec<String> = ["--seed", "99", "fib", "3"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            apply_seed(&args),
+            Ok(vec!["fib".to_string(), "3".to_string()])
+        );
+        assert_eq!(random::current_seed(), 99);
+
+        let args = vec!["--seed".to_string(), "x".to_string()];
+        assert_eq!(apply_seed(&args).unwrap_err().exit_code(), 2);
+        let args = vec!["--seed".to_string()];
+        assert_eq!(apply_seed(&args).unwrap_err().exit_code(), 2);
+
+        let args = vec!["--seed=7".to_string()];
+        assert_eq!(apply_seed(&args), Ok(Vec::new()));
+        assert_eq!(random::current_seed(), 7);
+    }
+
+    #[test]
+    fn test_seed_after_the_command_is_not_taken() {
+        let args: Vec<String> = ["lyrics", "--seed", "5"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        random::seed(11);
+        assert_eq!(apply_seed(&args), Ok(args.clone()));
+        assert_ne!(random::current_seed(), 5);
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        let (result, _) = run_command(&["dance"]);
+        assert_eq!(
+            result,
+            Err(CliError::Usage("Unknown command \"dance\"".to_string()))
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use termion::clear;
+
+/// Where every program reads its input from and writes its output to.
+///
+/// `write!` and `writeln!` work directly on a console, so programs can print with
+/// `writeln!(console, "...")` without handling an `io::Result`.
+pub trait Console {
+    /// Read one line of input without the trailing newline. Returns `None` once the input is
+    /// exhausted or can no longer be read.
+    fn read_line(&mut self) -> Option<String>;
+
+    /// Write text as-is, without appending a newline.
+    fn write_str(&mut self, text: &str);
+
+    /// Clear the screen
+    fn clear(&mut self);
+
+    /// Whether output goes to an interactive terminal that can be put in raw mode.
+    fn is_terminal(&self) -> bool {
+        false
+    }
+
+    /// Hook used by the `write!` and `writeln!` macros.
+    fn write_fmt(&mut self, args: fmt::Arguments) {
+        match args.as_str() {
+            Some(text) => self.write_str(text),
+            None => self.write_str(&args.to_string()),
+        }
+    }
+}
+
+/// Console backed by the process' stdin and stdout, clearing the screen with termion.
+#[derive(Default)]
+pub struct StdConsole;
+
+impl StdConsole {
+    pub fn new() -> Self {
+        StdConsole
+    }
+}
+
+impl Console for StdConsole {
+    fn read_line(&mut self) -> Option<String> {
+        let mut input = String::new();
+        match io::stdin().lock().read_line(&mut input) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(input.trim_end_matches(['\r', '\n']).to_string()),
+        }
+    }
+
+    fn write_str(&mut self, text: &str) {
+        let mut stdout = io::stdout().lock();
+        // Output is best effort: a closed stdout should not bring the program down.
+        let _ = stdout.write_all(text.as_bytes());
+        let _ = stdout.flush();
+    }
+
+    fn clear(&mut self) {
+        self.write_str(clear::All.as_ref());
+    }
+
+    fn is_terminal(&self) -> bool {
+        termion::is_tty(&io::stdout())
+    }
+}
+
+/// In-memory console fed from a fixed script of input lines. Everything written to it is
+/// captured so a whole session can be checked afterwards.
+#[derive(Default)]
+pub struct ScriptedConsole {
+    input: VecDeque<String>,
+    output: String,
+    clears: usize,
+}
+
+impl ScriptedConsole {
+    /// Create a console that answers successive reads with `lines`, then reports end of input.
+    pub fn new<I, S>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        ScriptedConsole {
+            input: lines.into_iter().map(Into::into).collect(),
+            output: String::new(),
+            clears: 0,
+        }
+    }
+
+    /// Everything written so far.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Number of times the screen was cleared.
+    pub fn clears(&self) -> usize {
+        self.clears
+    }
+
+    /// Input lines that have not been consumed yet.
+    pub fn remaining_input(&self) -> usize {
+        self.input.len()
+    }
+}
+
+impl Console for ScriptedConsole {
+    fn read_line(&mut self) -> Option<String> {
+        self.input.pop_front()
+    }
+
+    fn write_str(&mut self, text: &str) {
+        self.output.push_str(text);
+    }
+
+    fn clear(&mut self) {
+        self.clears += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripted_console_reads_in_order() {
+        let mut console = ScriptedConsole::new(["1", "two"]);
+        assert_eq!(console.read_line(), Some("1".to_string()));
+        assert_eq!(console.remaining_input(), 1);
+        assert_eq!(console.read_line(), Some("two".to_string()));
+        assert_eq!(console.read_line(), None);
+    }
+
+    #[test]
+    fn test_scripted_console_captures_output() {
+        let mut console = ScriptedConsole::new(Vec::<String>::new());
+        let name = "world";
+        writeln!(console, "Hello, {}!", name);
+        write!(console, "no newline");
+        console.clear();
+        assert_eq!(console.output(), "Hello, world!\nno newline");
+        assert_eq!(console.clears(), 1);
+    }
+}
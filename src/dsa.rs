@@ -1,38 +1,14 @@
-use crate::utils;
-mod deque;
-mod heap;
-mod stack;
-mod tree;
+pub mod deque;
+pub mod heap;
+pub mod stack;
+pub mod tree;
 
-pub fn launch() {
-    loop {
-        utils::clear_screen();
-        println!("Data structures and algorithms");
-        println!("1. Stack");
-        println!("2. Deque");
-        println!("3. Heap");
-        println!("4. Binary Search Tree");
-        println!("0. Back");
+use crate::menu::Menu;
 
-        let selection = crate::utils::read_input();
-        let selection = match selection.trim().parse::<u32>() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Please enter a number");
-                return;
-            }
-        };
-
-        match selection {
-            1 => stack::launch(),
-            2 => deque::launch(),
-            3 => heap::launch(),
-            4 => tree::launch(),
-            0 => return,
-            _ => {
-                println!("Invalid selection");
-                return;
-            }
-        }
-    }
+pub fn menu() -> Menu<'static> {
+    Menu::new("Data structures and algorithms")
+        .entry("Stack", stack::launch)
+        .entry("Deque", deque::launch)
+        .entry("Heap", heap::launch)
+        .entry("Binary Search Tree", tree::launch)
 }
@@ -1,3 +1,4 @@
+use crate::console::Console;
 use crate::utils;
 use std::cell::RefCell;
 use std::fmt::Display;
@@ -165,16 +166,16 @@ where
         std::mem::swap(&mut self.header, &mut self.trailer);
     }
 
-    fn print(&self) {
-        print!("Header <-> ");
+    fn print(&self, console: &mut dyn Console) {
+        write!(console, "Header <-> ");
         let mut curr_node = self.header.borrow().next.clone();
         while let Some(node) = curr_node {
             let node_ref = node.borrow();
             if node.borrow().next.is_none() {
-                println!("Trailer");
+                writeln!(console, "Trailer");
                 break;
             }
-            print!("{} <-> ", node_ref.val);
+            write!(console, "{} <-> ", node_ref.val);
             curr_node = node_ref.next.clone();
         }
     }
@@ -259,45 +260,47 @@ where
         self.list.reverse();
     }
 
-    fn print(&self) {
-        self.list.print();
+    fn print(&self, console: &mut dyn Console) {
+        self.list.print(console);
     }
 }
 
-pub fn launch() {
+pub fn launch(console: &mut dyn Console) {
     let mut deque = Deque::new();
 
     loop {
-        utils::clear_screen();
-        deque.print();
-        println!("Deque Operations");
-        println!("1. Push Front / Initialize New Deque");
-        println!("2. Push Back");
-        println!("3. Pop Front");
-        println!("4. Pop Back");
-        println!("5. Peek Front");
-        println!("6. Peek Back");
-        println!("7. Check if Empty");
-        println!("8. Get Length");
-        println!("9. Convert Deque to Vector");
-        println!("10. Reverse Deque");
-        println!("0. Exit");
-
-        let choice = utils::read_input();
+        utils::clear_screen(console);
+        deque.print(console);
+        writeln!(console, "Deque Operations");
+        writeln!(console, "1. Push Front / Initialize New Deque");
+        writeln!(console, "2. Push Back");
+        writeln!(console, "3. Pop Front");
+        writeln!(console, "4. Pop Back");
+        writeln!(console, "5. Peek Front");
+        writeln!(console, "6. Peek Back");
+        writeln!(console, "7. Check if Empty");
+        writeln!(console, "8. Get Length");
+        writeln!(console, "9. Convert Deque to Vector");
+        writeln!(console, "10. Reverse Deque");
+        writeln!(console, "0. Exit");
+
+        let Some(choice) = console.read_line() else {
+            break;
+        };
         let choice = match choice.trim().parse::<u32>() {
             Ok(num) => num,
             Err(_) => {
-                println!("Invalid selection. Please enter a valid number.");
+                writeln!(console, "Invalid selection. Please enter a valid number.");
                 continue;
             }
         };
 
         match choice {
             1 => {
-                println!("Enter:");
-                println!("- a single value to push to front");
-                println!("- or multiple values separated by commas from front to back to initialize a new deque");
-                let input = utils::read_input();
+                writeln!(console, "Enter:");
+                writeln!(console, "- a single value to push to front");
+                writeln!(console, "- or multiple values separated by commas from front to back to initialize a new deque");
+                let input = utils::read_input(console);
                 let values: Vec<&str> = input.split(',').map(|s| s.trim()).collect();
 
                 if values.len() == 1 {
@@ -307,58 +310,58 @@ pub fn launch() {
                 }
             }
             2 => {
-                println!("Enter a single value to push to back:");
-                let input = utils::read_input();
+                writeln!(console, "Enter a single value to push to back:");
+                let input = utils::read_input(console);
                 deque.push_back(input);
             }
             3 => match deque.pop_front() {
-                Some(value) => println!("Popped from front: {}", value),
-                None => println!("Deque is empty."),
+                Some(value) => writeln!(console, "Popped from front: {}", value),
+                None => writeln!(console, "Deque is empty."),
             },
             4 => match deque.pop_back() {
-                Some(value) => println!("Popped from back: {}", value),
-                None => println!("Deque is empty."),
+                Some(value) => writeln!(console, "Popped from back: {}", value),
+                None => writeln!(console, "Deque is empty."),
             },
             5 => match deque.peek_front() {
-                Some(value) => println!("Front of deque: {}", value),
-                None => println!("Deque is empty."),
+                Some(value) => writeln!(console, "Front of deque: {}", value),
+                None => writeln!(console, "Deque is empty."),
             },
             6 => match deque.peek_back() {
-                Some(value) => println!("Back of deque: {}", value),
-                None => println!("Deque is empty."),
+                Some(value) => writeln!(console, "Back of deque: {}", value),
+                None => writeln!(console, "Deque is empty."),
             },
             7 => {
                 if deque.empty() {
-                    println!("Deque is empty.");
+                    writeln!(console, "Deque is empty.");
                 } else {
-                    println!("Deque is not empty.");
+                    writeln!(console, "Deque is not empty.");
                 }
             }
             8 => {
-                println!("Deque length: {}", deque.len());
+                writeln!(console, "Deque length: {}", deque.len());
             }
             9 => {
                 let vec = deque.to_vec();
-                println!("Deque as vector: {:?}", vec);
+                writeln!(console, "Deque as vector: {:?}", vec);
             }
             10 => {
                 deque.reverse();
-                println!("Deque reversed.");
+                writeln!(console, "Deque reversed.");
             }
             0 => {
                 break;
             }
             _ => {
-                println!("Invalid selection. Please enter a valid number.");
+                writeln!(console, "Invalid selection. Please enter a valid number.");
             }
         }
-        println!("Press Enter to continue...");
-        utils::read_input();
+        writeln!(console, "Press Enter to continue...");
+        utils::read_input(console);
     }
-    println!("Dropping deque...");
+    writeln!(console, "Dropping deque...");
     mem::drop(deque);
-    println!("Press Enter to confirm...");
-    utils::read_input();
+    writeln!(console, "Press Enter to confirm...");
+    utils::read_input(console);
 }
 
 #[cfg(test)]
@@ -1,14 +1,16 @@
+use crate::console::Console;
 use crate::utils;
 use std::fmt::Display;
 use std::mem;
 
-#[derive(Clone, Copy)]
-enum HeapType {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeapType {
     MinHeap,
     MaxHeap,
 }
 
-struct Heap<T> {
+/// A binary heap whose top is its smallest or largest value, depending on its `HeapType`.
+pub struct Heap<T> {
     data: Vec<T>,
     heap_type: HeapType,
 }
@@ -17,14 +19,14 @@ impl<T> Heap<T>
 where
     T: Ord + Clone + Display,
 {
-    fn new(heap_type: HeapType) -> Self {
+    pub fn new(heap_type: HeapType) -> Self {
         Heap {
             data: Vec::new(),
             heap_type,
         }
     }
 
-    fn from_vec(vec: Vec<T>, heap_type: HeapType) -> Self {
+    pub fn from_vec(vec: Vec<T>, heap_type: HeapType) -> Self {
         let mut heap = Heap {
             data: vec,
             heap_type,
@@ -59,24 +61,24 @@ where
         }
     }
 
-    fn is_empty(&self) -> bool {
+    pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
 
-    fn len(&self) -> usize {
+    pub fn len(&self) -> usize {
         self.data.len()
     }
 
-    fn peek(&self) -> Option<&T> {
+    pub fn peek(&self) -> Option<&T> {
         self.data.first()
     }
 
-    fn push(&mut self, val: T) {
+    pub fn push(&mut self, val: T) {
         self.data.push(val);
         self.heapify_up(self.data.len() - 1);
     }
 
-    fn pop(&mut self) -> Option<T> {
+    pub fn pop(&mut self) -> Option<T> {
         if self.data.is_empty() {
             return None;
         }
@@ -126,33 +128,44 @@ where
         }
     }
 
-    fn print(&self) {
+    fn print(&self, console: &mut dyn Console) {
         // arrange data in a tree format
         let mut level = 0;
         let mut index = 0;
         while index < self.data.len() {
             for _ in 0..2usize.pow(level) {
                 if index < self.data.len() {
-                    print!("{} ", self.data[index]);
+                    write!(console, "{} ", self.data[index]);
                     index += 1;
                 }
             }
-            println!();
+            writeln!(console);
             level += 1;
         }
     }
 }
-pub fn launch() {
-    utils::clear_screen();
-    println!("Please select the type of heap:");
-    println!("1. Min Heap");
-    println!("2. Max Heap");
+/// Sort `values` by building a heap from them and popping until it is empty. A min heap sorts
+/// in ascending order, a max heap in descending order.
+pub fn heap_sort(values: Vec<i32>, heap_type: HeapType) -> Vec<i32> {
+    let mut heap = Heap::from_vec(values, heap_type);
+    let mut sorted = Vec::with_capacity(heap.len());
+    while let Some(value) = heap.pop() {
+        sorted.push(value);
+    }
+    sorted
+}
 
-    let heap_type = match utils::read_input().trim().parse::<u32>() {
+pub fn launch(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    writeln!(console, "Please select the type of heap:");
+    writeln!(console, "1. Min Heap");
+    writeln!(console, "2. Max Heap");
+
+    let heap_type = match utils::read_input(console).trim().parse::<u32>() {
         Ok(1) => HeapType::MinHeap,
         Ok(2) => HeapType::MaxHeap,
         _ => {
-            println!("Invalid selection. Defaulting to Max Heap.");
+            writeln!(console, "Invalid selection. Defaulting to Max Heap.");
             HeapType::MaxHeap
         }
     };
@@ -160,42 +173,47 @@ pub fn launch() {
     let mut heap: Heap<i32> = Heap::new(heap_type);
 
     loop {
-        utils::clear_screen();
-        heap.print();
-        println!("Heap Operations");
-        println!("1. Push / Initialize New Heap");
-        println!("2. Pop");
-        println!("3. Peek");
-        println!("4. Replace Top");
-        println!("5. Merge with Another Heap");
-        println!("6. Check if Empty");
-        println!("7. Get Length");
-        println!("8. Convert Heap to Vector");
-        println!("0. Exit");
-
-        let choice = utils::read_input();
+        utils::clear_screen(console);
+        heap.print(console);
+        writeln!(console, "Heap Operations");
+        writeln!(console, "1. Push / Initialize New Heap");
+        writeln!(console, "2. Pop");
+        writeln!(console, "3. Peek");
+        writeln!(console, "4. Replace Top");
+        writeln!(console, "5. Merge with Another Heap");
+        writeln!(console, "6. Check if Empty");
+        writeln!(console, "7. Get Length");
+        writeln!(console, "8. Convert Heap to Vector");
+        writeln!(console, "0. Exit");
+
+        let Some(choice) = console.read_line() else {
+            break;
+        };
         let choice = match choice.trim().parse::<u32>() {
             Ok(num) => num,
             Err(_) => {
-                println!("Invalid selection. Please enter a valid number.");
+                writeln!(console, "Invalid selection. Please enter a valid number.");
                 continue;
             }
         };
 
         match choice {
             1 => {
-                println!("Enter:");
-                println!("- a single number to push");
-                println!("- or multiple numbers separated by commas to initialize a new heap");
-                let input = utils::read_input();
+                writeln!(console, "Enter:");
+                writeln!(console, "- a single number to push");
+                writeln!(
+                    console,
+                    "- or multiple numbers separated by commas to initialize a new heap"
+                );
+                let input = utils::read_input(console);
                 let values: Vec<&str> = input.split(',').map(|s| s.trim()).collect();
 
                 if values.len() == 1 {
                     if let Ok(value) = values[0].parse::<i32>() {
                         heap.push(value);
-                        println!("Pushed: {}", value);
+                        writeln!(console, "Pushed: {}", value);
                     } else {
-                        println!("Invalid input. Please enter a valid number.");
+                        writeln!(console, "Invalid input. Please enter a valid number.");
                     }
                 } else {
                     let parsed_values: Vec<i32> = values
@@ -203,69 +221,76 @@ pub fn launch() {
                         .filter_map(|s| s.parse::<i32>().ok())
                         .collect();
                     heap = Heap::from_vec(parsed_values, heap_type);
-                    println!("Created new heap from values");
+                    writeln!(console, "Created new heap from values");
                 }
             }
             2 => match heap.pop() {
-                Some(value) => println!("Popped: {}", value),
-                None => println!("Heap is empty."),
+                Some(value) => writeln!(console, "Popped: {}", value),
+                None => writeln!(console, "Heap is empty."),
             },
             3 => match heap.peek() {
-                Some(value) => println!("Top of heap: {}", value),
-                None => println!("Heap is empty."),
+                Some(value) => writeln!(console, "Top of heap: {}", value),
+                None => writeln!(console, "Heap is empty."),
             },
             4 => {
-                println!("Enter a value to replace the top element:");
-                if let Ok(value) = utils::read_input().parse::<i32>() {
+                writeln!(console, "Enter a value to replace the top element:");
+                if let Ok(value) = utils::read_input(console).parse::<i32>() {
                     match heap.replace(value) {
-                        Some(old_value) => println!("Replaced {} with {}", old_value, value),
-                        None => println!("Heap was empty, pushed {} as first element", value),
+                        Some(old_value) => {
+                            writeln!(console, "Replaced {} with {}", old_value, value)
+                        }
+                        None => {
+                            writeln!(console, "Heap was empty, pushed {} as first element", value)
+                        }
                     }
                 } else {
-                    println!("Invalid input. Please enter a valid number.");
+                    writeln!(console, "Invalid input. Please enter a valid number.");
                 }
             }
             5 => {
-                println!("Enter values separated by commas for the second heap:");
-                let input = utils::read_input();
+                writeln!(
+                    console,
+                    "Enter values separated by commas for the second heap:"
+                );
+                let input = utils::read_input(console);
                 let values: Vec<i32> = input
                     .split(',')
                     .filter_map(|s| s.trim().parse::<i32>().ok())
                     .collect();
                 let mut other_heap = Heap::from_vec(values, heap_type);
                 heap.merge(&mut other_heap);
-                println!("Heaps merged successfully");
+                writeln!(console, "Heaps merged successfully");
             }
             6 => {
                 if heap.is_empty() {
-                    println!("Heap is empty.");
+                    writeln!(console, "Heap is empty.");
                 } else {
-                    println!("Heap is not empty.");
+                    writeln!(console, "Heap is not empty.");
                 }
             }
             7 => {
-                println!("Heap length: {}", heap.len());
+                writeln!(console, "Heap length: {}", heap.len());
             }
             8 => {
                 let vec = heap.to_vec();
-                println!("Heap as vector: {:?}", vec);
+                writeln!(console, "Heap as vector: {:?}", vec);
             }
             0 => {
                 break;
             }
             _ => {
-                println!("Invalid selection. Please enter a valid number.");
+                writeln!(console, "Invalid selection. Please enter a valid number.");
             }
         }
 
-        println!("Press Enter to continue...");
-        utils::read_input();
+        writeln!(console, "Press Enter to continue...");
+        utils::read_input(console);
     }
 
-    println!("Dropping heap...");
+    writeln!(console, "Dropping heap...");
     mem::drop(heap);
-    println!("Press Enter to confirm...");
-    utils::read_input();
+    writeln!(console, "Press Enter to confirm...");
+    utils::read_input(console);
 }
 
 #[cfg(test)]
@@ -400,6 +425,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_heap_sort() {
+        assert_eq!(
+            heap_sort(vec![5, 3, 7, 1], HeapType::MinHeap),
+            vec![1, 3, 5, 7]
+        );
+        assert_eq!(
+            heap_sort(vec![5, 3, 7, 1], HeapType::MaxHeap),
+            vec![7, 5, 3, 1]
+        );
+        assert_eq!(heap_sort(vec![], HeapType::MinHeap), Vec::<i32>::new());
+    }
+
     // Mixed Type Tests
     #[test]
     fn test_string_max_heap() {
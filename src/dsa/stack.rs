@@ -1,3 +1,4 @@
+use crate::console::Console;
 use crate::utils;
 use std::fmt::Display;
 use std::mem;
@@ -150,10 +151,10 @@ where
         self.list.length == 0
     }
 
-    fn print(&self) {
+    fn print(&self, console: &mut dyn Console) {
         if self.is_empty() {
-            println!("(empty)");
-            println!("-------");
+            writeln!(console, "(empty)");
+            writeln!(console, "-------");
             return;
         }
 
@@ -168,11 +169,11 @@ where
 
         let elements = self.to_vec();
         for elem in elements.iter().rev() {
-            println!("| {:^max_width$} |", elem, max_width = max_width);
+            writeln!(console, "| {:^max_width$} |", elem, max_width = max_width);
         }
 
         // Print bottom border with dynamic width
-        println!("{}", "-".repeat(max_width + 4));
+        writeln!(console, "{}", "-".repeat(max_width + 4));
     }
 
     fn reverse(&mut self) {
@@ -180,37 +181,39 @@ where
     }
 }
 
-pub fn launch() {
+pub fn launch(console: &mut dyn Console) {
     let mut stack = Stack::new();
 
     loop {
-        utils::clear_screen();
-        stack.print();
-        println!("Data Structure Operations");
-        println!("1. Push onto Stack / Initialize New Stack");
-        println!("2. Pop from Stack");
-        println!("3. Peek at Stack");
-        println!("4. Check if Stack is empty");
-        println!("5. Get Stack length");
-        println!("6. Reverse Stack");
-        println!("7. Convert Stack to Vector");
-        println!("0. Exit");
-
-        let choice = utils::read_input();
+        utils::clear_screen(console);
+        stack.print(console);
+        writeln!(console, "Data Structure Operations");
+        writeln!(console, "1. Push onto Stack / Initialize New Stack");
+        writeln!(console, "2. Pop from Stack");
+        writeln!(console, "3. Peek at Stack");
+        writeln!(console, "4. Check if Stack is empty");
+        writeln!(console, "5. Get Stack length");
+        writeln!(console, "6. Reverse Stack");
+        writeln!(console, "7. Convert Stack to Vector");
+        writeln!(console, "0. Exit");
+
+        let Some(choice) = console.read_line() else {
+            break;
+        };
         let choice = match choice.trim().parse::<u32>() {
             Ok(num) => num,
             Err(_) => {
-                println!("Invalid selection. Please enter a valid number.");
+                writeln!(console, "Invalid selection. Please enter a valid number.");
                 continue;
             }
         };
 
         match choice {
             1 => {
-                println!("Enter:");
-                println!("- a value to push to the current stack");
-                println!("- or multiple values separated by commas (from bottom to top) to initialize a new stack");
-                let input = utils::read_input();
+                writeln!(console, "Enter:");
+                writeln!(console, "- a value to push to the current stack");
+                writeln!(console, "- or multiple values separated by commas (from bottom to top) to initialize a new stack");
+                let input = utils::read_input(console);
                 let values: Vec<&str> = input.split(',').map(|s| s.trim()).collect();
                 if values.len() == 1 {
                     stack.push(values[0].to_string());
@@ -219,59 +222,59 @@ pub fn launch() {
                 }
             }
             2 => match stack.pop() {
-                Some(value) => println!("Popped value: {}", value),
-                None => println!("Stack is empty."),
+                Some(value) => writeln!(console, "Popped value: {}", value),
+                None => writeln!(console, "Stack is empty."),
             },
             3 => match stack.peek() {
                 Some(peek) => {
-                    println!("Top value: {}", peek);
-                    println!("Do you want to change the value? (y/n)");
-                    let input = utils::read_input();
+                    writeln!(console, "Top value: {}", peek);
+                    writeln!(console, "Do you want to change the value? (y/n)");
+                    let input = utils::read_input(console);
                     if input.trim().to_lowercase() != "y" {
                         continue;
                     }
-                    println!("Change value to:");
-                    let new_value = utils::read_input();
+                    writeln!(console, "Change value to:");
+                    let new_value = utils::read_input(console);
                     if new_value.is_empty() {
-                        println!("No value entered.");
+                        writeln!(console, "No value entered.");
                     } else if let Some(peek_mut) = stack.peek_mut() {
                         peek_mut.replace_range(.., &new_value);
                     }
                 }
-                None => println!("Stack is empty."),
+                None => writeln!(console, "Stack is empty."),
             },
             4 => {
                 if stack.is_empty() {
-                    println!("Stack is empty.");
+                    writeln!(console, "Stack is empty.");
                 } else {
-                    println!("Stack is not empty.");
+                    writeln!(console, "Stack is not empty.");
                 }
             }
             5 => {
-                println!("Stack length: {}", stack.len());
+                writeln!(console, "Stack length: {}", stack.len());
             }
             6 => {
                 stack.reverse();
-                println!("Stack reversed.");
+                writeln!(console, "Stack reversed.");
             }
             7 => {
                 let vec = stack.to_vec();
-                println!("Stack as vector: {:?}", vec);
+                writeln!(console, "Stack as vector: {:?}", vec);
             }
             0 => {
                 break;
             }
             _ => {
-                println!("Invalid selection. Please enter a valid number.");
+                writeln!(console, "Invalid selection. Please enter a valid number.");
             }
         }
-        println!("Press Enter to continue...");
-        utils::read_input();
+        writeln!(console, "Press Enter to continue...");
+        utils::read_input(console);
     }
-    println!("Dropping stack");
+    writeln!(console, "Dropping stack");
     mem::drop(stack);
-    println!("Press Enter to confirm...");
-    utils::read_input();
+    writeln!(console, "Press Enter to confirm...");
+    utils::read_input(console);
 }
 
 #[cfg(test)]
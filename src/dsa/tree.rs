@@ -1,3 +1,4 @@
+use crate::console::Console;
 use crate::utils;
 use std::mem;
 
@@ -144,116 +145,118 @@ impl BinarySearchTree {
         }
     }
 
-    fn in_order_traversal(&self) {
-        fn traverse(node: &Option<Box<Node<i32>>>) {
+    fn in_order_traversal(&self, console: &mut dyn Console) {
+        fn traverse(node: &Option<Box<Node<i32>>>, console: &mut dyn Console) {
             if let Some(node) = node {
-                traverse(&node.left);
-                print!("{} ", node.value);
-                traverse(&node.right);
+                traverse(&node.left, console);
+                write!(console, "{} ", node.value);
+                traverse(&node.right, console);
             }
         }
-        traverse(&self.root);
+        traverse(&self.root, console);
     }
 }
 
-pub fn launch() {
+pub fn launch(console: &mut dyn Console) {
     let mut bst = BinarySearchTree::new();
 
     loop {
-        utils::clear_screen();
-        println!("Binary Search Tree Operations");
-        println!("1. Insert a value / Make a new tree");
-        println!("2. Search for a value");
-        println!("3. Delete a value");
-        println!("4. Display tree (In-order Traversal)");
-        println!("5. Check if tree is empty");
-        println!("6. Clear tree");
-        println!("0. Exit");
+        utils::clear_screen(console);
+        writeln!(console, "Binary Search Tree Operations");
+        writeln!(console, "1. Insert a value / Make a new tree");
+        writeln!(console, "2. Search for a value");
+        writeln!(console, "3. Delete a value");
+        writeln!(console, "4. Display tree (In-order Traversal)");
+        writeln!(console, "5. Check if tree is empty");
+        writeln!(console, "6. Clear tree");
+        writeln!(console, "0. Exit");
 
-        let choice = utils::read_input();
+        let Some(choice) = console.read_line() else {
+            break;
+        };
         let choice = match choice.trim().parse::<u32>() {
             Ok(num) => num,
             Err(_) => {
-                println!("Invalid selection. Please enter a valid number.");
+                writeln!(console, "Invalid selection. Please enter a valid number.");
                 continue;
             }
         };
 
         match choice {
             1 => {
-                println!("Enter a value to insert, or multiple values separated by spaces to make a new tree:");
-                let input = utils::read_input();
+                writeln!(console, "Enter a value to insert, or multiple values separated by spaces to make a new tree:");
+                let input = utils::read_input(console);
                 let values: Vec<i32> = input
                     .split_whitespace()
                     .filter_map(|s| s.parse::<i32>().ok())
                     .collect();
                 if values.is_empty() {
-                    println!("No valid values entered.");
+                    writeln!(console, "No valid values entered.");
                 } else {
                     bst.root = None;
                     for value in values {
                         bst.insert(value);
                     }
-                    println!("Inserted values into the BST.");
+                    writeln!(console, "Inserted values into the BST.");
                 }
             }
             2 => {
-                println!("Enter a value to search:");
-                let input = utils::read_input();
+                writeln!(console, "Enter a value to search:");
+                let input = utils::read_input(console);
                 if let Ok(value) = input.trim().parse::<i32>() {
                     if bst.search(value) {
-                        println!("Value {} found in the BST.", value);
+                        writeln!(console, "Value {} found in the BST.", value);
                     } else {
-                        println!("Value {} not found in the BST.", value);
+                        writeln!(console, "Value {} not found in the BST.", value);
                     }
                 } else {
-                    println!("Invalid input. Please enter a valid integer.");
+                    writeln!(console, "Invalid input. Please enter a valid integer.");
                 }
             }
             3 => {
-                println!("Enter a value to delete:");
-                let input = utils::read_input();
+                writeln!(console, "Enter a value to delete:");
+                let input = utils::read_input(console);
                 if let Ok(value) = input.trim().parse::<i32>() {
                     if bst.delete(value) {
-                        println!("Value {} deleted from the BST.", value);
+                        writeln!(console, "Value {} deleted from the BST.", value);
                     } else {
-                        println!("Value {} not found in the BST.", value);
+                        writeln!(console, "Value {} not found in the BST.", value);
                     }
                 } else {
-                    println!("Invalid input. Please enter a valid integer.");
+                    writeln!(console, "Invalid input. Please enter a valid integer.");
                 }
             }
             4 => {
-                println!("In-order traversal of BST:");
-                bst.in_order_traversal();
+                writeln!(console, "In-order traversal of BST:");
+                bst.in_order_traversal(console);
             }
             5 => {
                 if bst.is_empty() {
-                    println!("The BST is empty.");
+                    writeln!(console, "The BST is empty.");
                 } else {
-                    println!("The BST is not empty.");
+                    writeln!(console, "The BST is not empty.");
                 }
             }
             6 => {
                 bst.root = None;
-                println!("The BST has been cleared.");
+                writeln!(console, "The BST has been cleared.");
             }
             0 => {
                 break;
             }
             _ => {
-                println!("Invalid selection. Please enter a valid number.");
+                writeln!(console, "Invalid selection. Please enter a valid number.");
             }
         }
 
-        println!("Press Enter to continue...");
-        utils::read_input();
+        writeln!(console, "Press Enter to continue...");
+        utils::read_input(console);
     }
 
-    println!("Dropping BST...");
+    writeln!(console, "Dropping BST...");
     mem::drop(bst);
-    println!("Press Enter to confirm...");
-    utils::read_input();
+    writeln!(console, "Press Enter to confirm...");
+    utils::read_input(console);
 }
 
 #[cfg(test)]
@@ -1,42 +1,16 @@
-mod add_employee;
-mod find_median;
-mod pig_latin;
+pub mod add_employee;
+pub mod find_median;
+pub mod pig_latin;
+pub mod statistics;
+pub mod word_games;
 
-use crate::utils;
-pub fn launch() {
-    loop {
-        utils::clear_screen();
-        println!("Exercises:");
-        println!("1. Find Median");
-        println!("2. Pig Latin");
-        println!("3. Add Employee");
-        println!("0. Back");
+use crate::menu::Menu;
 
-        let selection = utils::read_input();
-
-        let selection = match selection.trim().parse::<u32>() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Invalid selection");
-                continue;
-            }
-        };
-        println!("Selection: {}", selection);
-        match selection {
-            1 => find_median::launch(),
-            2 => pig_latin::launch(),
-            3 => add_employee::launch(),
-            0 => break,
-            _ => {
-                println!("Invalid selection");
-                continue;
-            }
-        }
-
-        println!("Do you want to run another exercise? (y/n)");
-        match utils::read_input().trim() {
-            "y" => continue,
-            _ => break,
-        }
-    }
+pub fn menu() -> Menu<'static> {
+    Menu::new("Exercises")
+        .submenu("Find Median", find_median::menu())
+        .submenu("Pig Latin", pig_latin::menu())
+        .entry("Add Employee", add_employee::launch)
+        .entry("Statistics Summary", statistics::launch)
+        .submenu("Word Games", word_games::menu())
 }
@@ -1,153 +1,1098 @@
-use std::collections::{HashMap, HashSet};
+pub mod storage;
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use crate::console::Console;
 use crate::utils;
 
-struct Company {
-    employee_department: HashMap<String, HashSet<String>>,
-    department_employee: HashMap<String, HashSet<String>>,
+/// Identifies an employee. IDs are handed out in order and never reused.
+pub type EmployeeId = u32;
+
+/// What is recorded about an employee. The ID is the key the company keeps it under.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Employee {
+    pub name: String,
+    pub title: String,
+    /// Yearly salary in whole currency units, 0 when unknown.
+    pub salary: u64,
+    pub hire_date: Option<NaiveDate>,
+    pub manager: Option<EmployeeId>,
+}
+
+impl Employee {
+    /// An employee of whom only the name is known.
+    pub fn named(name: &str) -> Employee {
+        Employee {
+            name: name.to_string(),
+            ..Employee::default()
+        }
+    }
+}
+
+/// Employees and the departments they work in. An employee can be in several departments and
+/// a department can be empty. Employees are told apart by ID, so names can repeat.
+#[derive(Debug)]
+pub struct Company {
+    employees: BTreeMap<EmployeeId, Employee>,
+    employee_department: HashMap<EmployeeId, HashSet<String>>,
+    department_employee: HashMap<String, HashSet<EmployeeId>>,
+    next_id: EmployeeId,
+}
+
+impl Default for Company {
+    fn default() -> Self {
+        Company::new()
+    }
 }
 
 impl Company {
-    fn new() -> Company {
+    pub fn new() -> Company {
         Company {
+            employees: BTreeMap::new(),
             employee_department: HashMap::new(),
             department_employee: HashMap::new(),
+            next_id: 1,
         }
     }
 
-    /// Add an employee to the company. Returns true if the employee was added successfully, false
-    /// if the employee is already in the department.
-    fn add_employee(&mut self, name: &str, department: &str) -> bool {
-        let dept_already_in_empl = self
-            .employee_department
-            .entry(name.to_string())
+    pub fn len(&self) -> usize {
+        self.employees.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.employees.is_empty()
+    }
+
+    /// Add a new employee to a department. Returns the ID they were given.
+    pub fn hire(&mut self, employee: Employee, department: &str) -> Result<EmployeeId, String> {
+        let id = self.next_id;
+        self.insert(id, employee, department)?;
+        Ok(id)
+    }
+
+    /// Add an employee under a chosen ID that is not in use yet.
+    fn insert(
+        &mut self,
+        id: EmployeeId,
+        employee: Employee,
+        department: &str,
+    ) -> Result<(), String> {
+        if self.employees.contains_key(&id) {
+            return Err(format!("Employee ID {} is already in use", id));
+        }
+        let next_id = id
+            .checked_add(1)
+            .ok_or_else(|| format!("Employee ID {} is too large", id))?;
+        if department.is_empty() {
+            return Err("Department name cannot be empty".to_string());
+        }
+        self.validate(id, &employee)?;
+        self.employees.insert(id, employee);
+        self.employee_department
+            .entry(id)
             .or_default()
             .insert(department.to_string());
-        let empl_already_in_dept = self
-            .department_employee
+        self.department_employee
+            .entry(department.to_string())
+            .or_default()
+            .insert(id);
+        self.next_id = self.next_id.max(next_id);
+        Ok(())
+    }
+
+    /// Check the record `employee` would have under `id`: it needs a name, and its manager
+    /// must exist without the employee ending up managing themselves.
+    fn validate(&self, id: EmployeeId, employee: &Employee) -> Result<(), String> {
+        if employee.name.trim().is_empty() {
+            return Err("Employee name cannot be empty".to_string());
+        }
+        let mut manager = employee.manager;
+        // Every step up the chain visits a different employee, unless there is a loop
+        for _ in 0..=self.employees.len() {
+            let Some(current) = manager else {
+                return Ok(());
+            };
+            if current == id {
+                return Err(format!("Employee {} cannot manage themselves", id));
+            }
+            manager = self
+                .employees
+                .get(&current)
+                .ok_or_else(|| format!("Manager {} not found", current))?
+                .manager;
+        }
+        Err(format!("The managers of employee {} form a loop", id))
+    }
+
+    /// Replace the record of an employee, keeping their ID and departments.
+    pub fn update(&mut self, id: EmployeeId, employee: Employee) -> Result<(), String> {
+        if !self.employees.contains_key(&id) {
+            return Err(format!("Employee {} not found", id));
+        }
+        self.validate(id, &employee)?;
+        self.employees.insert(id, employee);
+        Ok(())
+    }
+
+    /// Add an employee to one more department.
+    pub fn assign(&mut self, id: EmployeeId, department: &str) -> Result<(), String> {
+        let departments = self
+            .employee_department
+            .get_mut(&id)
+            .ok_or_else(|| format!("Employee {} not found", id))?;
+        if department.is_empty() {
+            return Err("Department name cannot be empty".to_string());
+        }
+        if !departments.insert(department.to_string()) {
+            return Err(format!("Employee {} is already in {}", id, department));
+        }
+        self.department_employee
             .entry(department.to_string())
             .or_default()
-            .insert(name.to_string());
+            .insert(id);
+        Ok(())
+    }
+
+    /// Add a department without employees. Returns false if it already exists.
+    pub fn add_department(&mut self, name: &str) -> bool {
+        if self.department_employee.contains_key(name) {
+            return false;
+        }
+        self.department_employee
+            .insert(name.to_string(), HashSet::new());
+        true
+    }
+
+    /// Remove an employee from the company and return their record. The departments stay,
+    /// even when they become empty, and the people they managed are left without a manager.
+    pub fn remove_employee(&mut self, id: EmployeeId) -> Result<Employee, String> {
+        let employee = self
+            .employees
+            .remove(&id)
+            .ok_or_else(|| format!("Employee {} not found", id))?;
+        for department in self.employee_department.remove(&id).unwrap_or_default() {
+            if let Some(employees) = self.department_employee.get_mut(&department) {
+                employees.remove(&id);
+            }
+        }
+        for report in self.employees.values_mut() {
+            if report.manager == Some(id) {
+                report.manager = None;
+            }
+        }
+        Ok(employee)
+    }
+
+    /// Move an employee from one department to another, creating the new department if needed.
+    pub fn move_employee(&mut self, id: EmployeeId, from: &str, to: &str) -> Result<(), String> {
+        let departments = self
+            .employee_department
+            .get_mut(&id)
+            .ok_or_else(|| format!("Employee {} not found", id))?;
+        if !departments.contains(from) {
+            return Err(format!("Employee {} is not in {}", id, from));
+        }
+        if to.is_empty() {
+            return Err("Department name cannot be empty".to_string());
+        }
+        if departments.contains(to) {
+            return Err(format!("Employee {} is already in {}", id, to));
+        }
+        departments.remove(from);
+        departments.insert(to.to_string());
+        if let Some(employees) = self.department_employee.get_mut(from) {
+            employees.remove(&id);
+        }
+        self.department_employee
+            .entry(to.to_string())
+            .or_default()
+            .insert(id);
+        Ok(())
+    }
 
-        dept_already_in_empl && empl_already_in_dept
+    /// Give a department a new name that is not in use yet.
+    pub fn rename_department(&mut self, old: &str, new: &str) -> Result<(), String> {
+        if new.is_empty() {
+            return Err("Department name cannot be empty".to_string());
+        }
+        if self.department_employee.contains_key(new) {
+            return Err(format!("Department {} already exists", new));
+        }
+        let employees = self
+            .department_employee
+            .remove(old)
+            .ok_or_else(|| format!("Department {} not found", old))?;
+        for employee in &employees {
+            if let Some(departments) = self.employee_department.get_mut(employee) {
+                departments.remove(old);
+                departments.insert(new.to_string());
+            }
+        }
+        self.department_employee.insert(new.to_string(), employees);
+        Ok(())
+    }
+
+    /// Delete a department that has no employees left.
+    pub fn delete_department(&mut self, name: &str) -> Result<(), String> {
+        match self.department_employee.get(name) {
+            None => Err(format!("Department {} not found", name)),
+            Some(employees) if !employees.is_empty() => Err(format!(
+                "Department {} still has {} employee(s)",
+                name,
+                employees.len()
+            )),
+            Some(_) => {
+                self.department_employee.remove(name);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn employee(&self, id: EmployeeId) -> Option<&Employee> {
+        self.employees.get(&id)
+    }
+
+    /// Every employee, by ID.
+    pub fn employees(&self) -> impl Iterator<Item = (EmployeeId, &Employee)> {
+        self.employees.iter().map(|(&id, employee)| (id, employee))
+    }
+
+    /// Every employee with this name, ignoring case and surrounding spaces, by ID.
+    pub fn find_by_name(&self, name: &str) -> Vec<(EmployeeId, &Employee)> {
+        let name = name.trim().to_lowercase();
+        self.employees()
+            .filter(|(_, employee)| employee.name.trim().to_lowercase() == name)
+            .collect()
+    }
+
+    /// The employees of a department sorted by name, or `None` if there is no such department.
+    pub fn employees_in(&self, department: &str) -> Option<Vec<(EmployeeId, &Employee)>> {
+        let ids = self.department_employee.get(department)?;
+        let mut employees: Vec<(EmployeeId, &Employee)> = ids
+            .iter()
+            .filter_map(|&id| Some((id, self.employees.get(&id)?)))
+            .collect();
+        employees.sort_by(|(a_id, a), (b_id, b)| (&a.name, a_id).cmp(&(&b.name, b_id)));
+        Some(employees)
+    }
+
+    /// Every department, sorted.
+    pub fn departments(&self) -> Vec<&str> {
+        let mut departments: Vec<&str> = self
+            .department_employee
+            .keys()
+            .map(String::as_str)
+            .collect();
+        departments.sort();
+        departments
+    }
+
+    /// The departments an employee is in, sorted.
+    pub fn departments_of(&self, id: EmployeeId) -> Vec<&str> {
+        let mut departments: Vec<&str> = self
+            .employee_department
+            .get(&id)
+            .map(|departments| departments.iter().map(String::as_str).collect())
+            .unwrap_or_default();
+        departments.sort();
+        departments
+    }
+
+    /// Check that every employee has a valid record and at least one department, and that both
+    /// department maps describe the same memberships. Returns the first inconsistency found.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        for (&id, employee) in &self.employees {
+            if id >= self.next_id {
+                return Err(format!("Employee {} is not below the next ID", id));
+            }
+            self.validate(id, employee)?;
+            if !self.employee_department.contains_key(&id) {
+                return Err(format!("Employee {} has no department", id));
+            }
+        }
+        for (employee, departments) in &self.employee_department {
+            if !self.employees.contains_key(employee) {
+                return Err(format!(
+                    "Employee {} has departments but no record",
+                    employee
+                ));
+            }
+            if departments.is_empty() {
+                return Err(format!("Employee {} has no department", employee));
+            }
+            for department in departments {
+                let listed = self
+                    .department_employee
+                    .get(department)
+                    .is_some_and(|employees| employees.contains(employee));
+                if !listed {
+                    return Err(format!(
+                        "Employee {} is in {} but not listed there",
+                        employee, department
+                    ));
+                }
+            }
+        }
+        for (department, employees) in &self.department_employee {
+            for employee in employees {
+                let listed = self
+                    .employee_department
+                    .get(employee)
+                    .is_some_and(|departments| departments.contains(department));
+                if !listed {
+                    return Err(format!(
+                        "Department {} lists employee {} who is not in it",
+                        department, employee
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// One line about an employee, such as "#2 Amir (Sales lead, hired 2021-03-01, reports to
+    /// #1 Sally)".
+    pub fn describe(&self, id: EmployeeId) -> String {
+        let Some(employee) = self.employee(id) else {
+            return format!("#{} (unknown)", id);
+        };
+        let mut details = Vec::new();
+        if !employee.title.is_empty() {
+            details.push(employee.title.clone());
+        }
+        if employee.salary > 0 {
+            details.push(format!("salary {}", employee.salary));
+        }
+        if let Some(hire_date) = employee.hire_date {
+            details.push(format!("hired {}", hire_date));
+        }
+        if let Some(manager) = employee
+            .manager
+            .and_then(|id| Some((id, self.employee(id)?)))
+        {
+            details.push(format!("reports to #{} {}", manager.0, manager.1.name));
+        }
+        if details.is_empty() {
+            format!("#{} {}", id, employee.name)
+        } else {
+            format!("#{} {} ({})", id, employee.name, details.join(", "))
+        }
     }
 }
 
-pub fn launch() {
-    utils::clear_screen();
-    let mut company = Company::new();
-    println!("Add or see employees to your company!");
+/// Parse a hire date written as YYYY-MM-DD.
+pub fn parse_date(text: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("\"{}\" is not a valid date, use YYYY-MM-DD", text.trim()))
+}
+
+/// Parse a salary in whole currency units.
+pub fn parse_salary(text: &str) -> Result<u64, String> {
+    text.trim()
+        .parse()
+        .map_err(|_| format!("\"{}\" is not a valid salary", text.trim()))
+}
+
+/// Ask for a value, reporting an empty answer. Returns `None` when nothing was entered.
+fn ask(console: &mut dyn Console, prompt: &str, what: &str) -> Option<String> {
+    writeln!(console, "{}", prompt);
+    let answer = utils::read_input(console);
+    if answer.is_empty() {
+        writeln!(console, "{} cannot be empty", what);
+        return None;
+    }
+    Some(answer)
+}
+
+/// Ask for an optional value until it parses. Returns `None` when nothing was entered.
+fn ask_optional<T>(
+    console: &mut dyn Console,
+    prompt: &str,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Option<T> {
     loop {
-        utils::clear_screen();
-        println!("1. Add a new employee");
-        println!("2. Search the company");
-        println!("0. Back");
+        writeln!(console, "{}", prompt);
+        let answer = utils::read_input(console);
+        if answer.is_empty() {
+            return None;
+        }
+        match parse(&answer) {
+            Ok(value) => return Some(value),
+            Err(message) => writeln!(console, "{}", message),
+        }
+    }
+}
+
+/// Find the employee meant by an ID or a name. A name shared by several employees is
+/// ambiguous, so the matches are listed and an ID is needed.
+pub fn resolve_employee(company: &Company, text: &str) -> Result<EmployeeId, String> {
+    let text = text.trim().trim_start_matches('#');
+    if let Ok(id) = text.parse::<EmployeeId>() {
+        return match company.employee(id) {
+            Some(_) => Ok(id),
+            None => Err(format!("Employee {} not found", id)),
+        };
+    }
+    match company.find_by_name(text).as_slice() {
+        [] => Err(format!("Employee {} not found", text)),
+        [(id, _)] => Ok(*id),
+        matches => {
+            let matches: Vec<String> = matches
+                .iter()
+                .map(|(id, _)| company.describe(*id))
+                .collect();
+            Err(format!(
+                "Several employees are called {}, enter an ID instead: {}",
+                text,
+                matches.join("; ")
+            ))
+        }
+    }
+}
+
+/// Ask which employee is meant, by ID or name.
+fn choose_employee(
+    console: &mut dyn Console,
+    company: &Company,
+    prompt: &str,
+) -> Option<EmployeeId> {
+    let answer = ask(console, prompt, "Employee")?;
+    match resolve_employee(company, &answer) {
+        Ok(id) => Some(id),
+        Err(message) => {
+            writeln!(console, "{}", message);
+            None
+        }
+    }
+}
+
+/// Report the outcome of a change to the company. Returns whether it was made.
+fn report(console: &mut dyn Console, result: Result<(), String>, success: &str) -> bool {
+    match &result {
+        Ok(()) => writeln!(console, "{}", success),
+        Err(message) => writeln!(console, "{}", message),
+    }
+    result.is_ok()
+}
+
+fn add_employee(console: &mut dyn Console, company: &mut Company) -> bool {
+    utils::clear_screen(console);
+    let Some(name) = ask(console, "Enter employee name:", "Employee name") else {
+        return false;
+    };
+    let Some(department) = ask(console, "Enter department:", "Department name") else {
+        return false;
+    };
+    let title = ask_optional(
+        console,
+        "Enter job title, or press Enter to skip:",
+        |title| Ok(title.to_string()),
+    );
+    let salary = ask_optional(
+        console,
+        "Enter yearly salary, or press Enter to skip:",
+        parse_salary,
+    );
+    let hire_date = ask_optional(
+        console,
+        "Enter hire date (YYYY-MM-DD), or press Enter to skip:",
+        parse_date,
+    );
+    let manager = ask_optional(
+        console,
+        "Enter the manager's ID or name, or press Enter for none:",
+        |text| resolve_employee(company, text),
+    );
+    let employee = Employee {
+        name,
+        title: title.unwrap_or_default(),
+        salary: salary.unwrap_or_default(),
+        hire_date,
+        manager,
+    };
+    match company.hire(employee, &department) {
+        Ok(id) => {
+            writeln!(console, "Added {}", company.describe(id));
+            true
+        }
+        Err(message) => {
+            writeln!(console, "{}", message);
+            false
+        }
+    }
+}
+
+fn remove_employee(console: &mut dyn Console, company: &mut Company) -> bool {
+    let Some(id) = choose_employee(console, company, "Enter employee ID or name:") else {
+        return false;
+    };
+    let result = company.remove_employee(id).map(|_| ());
+    report(console, result, "Employee removed")
+}
+
+fn move_employee(console: &mut dyn Console, company: &mut Company) -> bool {
+    let Some(id) = choose_employee(console, company, "Enter employee ID or name:") else {
+        return false;
+    };
+    let Some(from) = ask(console, "Move from department:", "Department name") else {
+        return false;
+    };
+    let Some(to) = ask(console, "Move to department:", "Department name") else {
+        return false;
+    };
+    report(
+        console,
+        company.move_employee(id, &from, &to),
+        "Employee moved",
+    )
+}
+
+fn rename_department(console: &mut dyn Console, company: &mut Company) -> bool {
+    let Some(old) = ask(console, "Enter department:", "Department name") else {
+        return false;
+    };
+    let Some(new) = ask(console, "Enter the new name:", "Department name") else {
+        return false;
+    };
+    report(
+        console,
+        company.rename_department(&old, &new),
+        "Department renamed",
+    )
+}
+
+fn delete_department(console: &mut dyn Console, company: &mut Company) -> bool {
+    let Some(name) = ask(console, "Enter department:", "Department name") else {
+        return false;
+    };
+    report(
+        console,
+        company.delete_department(&name),
+        "Department deleted",
+    )
+}
 
-        let selection = utils::read_input();
+/// Browse the company until the user goes back.
+fn search(console: &mut dyn Console, company: &Company) {
+    loop {
+        utils::clear_screen(console);
+        writeln!(console, "1. View all employees");
+        writeln!(console, "2. View employees by department");
+        writeln!(console, "3. Find employees by name");
+        writeln!(console, "4. Look up an employee by ID");
+        writeln!(console, "0. Back");
 
+        let Some(selection) = console.read_line() else {
+            break;
+        };
         let selection = match selection.trim().parse::<u32>() {
             Ok(num) => num,
             Err(_) => {
-                println!("Invalid selection. Please enter a valid number.");
+                writeln!(console, "Invalid selection. Please enter a valid number.");
                 continue;
             }
         };
 
         match selection {
             1 => {
-                utils::clear_screen();
-                println!("Enter employee name:");
-                let name = utils::read_input();
-                if name.trim().is_empty() {
-                    println!("Employee name cannot be empty");
-                    continue;
-                }
-                println!("Enter department:");
-                let department = utils::read_input();
-                if department.trim().is_empty() {
-                    println!("Department name cannot be empty");
-                    continue;
+                utils::clear_screen(console);
+                if company.departments().is_empty() {
+                    writeln!(console, "No employees in the company");
                 }
-                let employee_already_exist = company.add_employee(name.trim(), department.trim());
-                if employee_already_exist {
-                    println!("Employee already exists in the department");
-                } else {
-                    println!("Employee added successfully to the department");
+                for department in company.departments() {
+                    writeln!(console, "Department: {}", department);
+                    writeln!(console, "{}", "=".repeat(20));
+                    for (id, _) in company.employees_in(department).unwrap_or_default() {
+                        writeln!(console, "{}", company.describe(id));
+                    }
+                    writeln!(console);
                 }
             }
-            2 => loop {
-                utils::clear_screen();
-                println!("1. View all employees");
-                println!("2. View employees by department");
-                println!("3. View departments by employee");
-                println!("0. Back");
-
-                let selection = utils::read_input();
-                let selection = match selection.trim().parse::<u32>() {
-                    Ok(num) => num,
-                    Err(_) => {
-                        println!("Invalid selection. Please enter a valid number.");
-                        continue;
-                    }
-                };
-
-                match selection {
-                    1 => {
-                        utils::clear_screen();
-                        if company.employee_department.is_empty() {
-                            println!("No employees in the company");
-                        } else {
-                            for (department, employees) in &company.department_employee {
-                                println!("Department: {}", department);
-                                println!("{}", "=".repeat(20));
-                                for (i, employee) in employees.iter().enumerate() {
-                                    println!("{}. {}", i, employee);
-                                }
-                                println!();
-                            }
+            2 => {
+                utils::clear_screen(console);
+                writeln!(console, "Enter department:");
+                let department = utils::read_input(console);
+                match company.employees_in(&department) {
+                    Some(employees) => {
+                        for (id, _) in employees {
+                            writeln!(console, "{}", company.describe(id));
                         }
                     }
-                    2 => {
-                        utils::clear_screen();
-                        println!("Enter department:");
-                        let department = utils::read_input();
-                        match company.department_employee.get(&department) {
-                            Some(employees) => {
-                                for (i, employee) in employees.iter().enumerate() {
-                                    println!("{}. {}", i, employee);
-                                }
-                            }
-                            None => println!("Department not found"),
-                        }
-                    }
-                    3 => {
-                        utils::clear_screen();
-                        println!("Enter employee name:");
-                        let name = utils::read_input();
-                        match company.employee_department.get(&name) {
-                            Some(departments) => {
-                                for (i, department) in departments.iter().enumerate() {
-                                    println!("{}. {}", i, department);
-                                }
-                            }
-                            None => println!("Employee not found"),
-                        }
-                    }
-                    0 => break,
-                    _ => {
-                        println!("Invalid selection");
-                        continue;
-                    }
+                    None => writeln!(console, "Department not found"),
+                }
+            }
+            3 => {
+                utils::clear_screen(console);
+                writeln!(console, "Enter employee name:");
+                let name = utils::read_input(console);
+                let matches = company.find_by_name(&name);
+                if matches.is_empty() {
+                    writeln!(console, "Employee not found");
                 }
+                for (id, _) in matches {
+                    writeln!(
+                        console,
+                        "{} in {}",
+                        company.describe(id),
+                        company.departments_of(id).join(", ")
+                    );
+                }
+            }
+            4 => {
+                utils::clear_screen(console);
+                writeln!(console, "Enter employee ID:");
+                let id = utils::read_input(console);
+                match id.trim_start_matches('#').parse::<EmployeeId>() {
+                    Ok(id) if company.employee(id).is_some() => writeln!(
+                        console,
+                        "{} in {}",
+                        company.describe(id),
+                        company.departments_of(id).join(", ")
+                    ),
+                    _ => writeln!(console, "Employee not found"),
+                }
+            }
+            0 => break,
+            _ => {
+                writeln!(console, "Invalid selection");
+                continue;
+            }
+        }
+
+        writeln!(console, "Press enter to continue...");
+        utils::read_input(console);
+    }
+}
+
+fn save_to_file(console: &mut dyn Console, company: &Company) -> bool {
+    let Some(path) = ask(console, "Save to file (.json or .csv):", "File name") else {
+        return false;
+    };
+    match storage::save(company, Path::new(&path)) {
+        Ok(()) => writeln!(console, "Saved to {}, sorted by department and name", path),
+        Err(message) => writeln!(console, "{}", message),
+    }
+    false
+}
 
-                println!("Press enter to continue...");
-                utils::read_input();
-            },
+fn load_from_file(console: &mut dyn Console, company: &mut Company) -> bool {
+    let Some(path) = ask(console, "Load from file (.json or .csv):", "File name") else {
+        return false;
+    };
+    match storage::load(Path::new(&path)) {
+        Ok(loaded) => {
+            *company = loaded;
+            writeln!(console, "Loaded {}", path);
+            true
+        }
+        Err(message) => {
+            writeln!(console, "{}", message);
+            false
+        }
+    }
+}
+
+fn import_roster(console: &mut dyn Console, company: &mut Company) -> bool {
+    let Some(path) = ask(console, "Import CSV roster:", "File name") else {
+        return false;
+    };
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) => {
+            writeln!(console, "Could not read {}: {}", path, err);
+            return false;
+        }
+    };
+    let import = storage::import_csv(company, &text);
+    writeln!(console, "Imported {} rows", import.added);
+    for error in &import.errors {
+        writeln!(console, "  {}", error);
+    }
+    import.added > 0
+}
+
+pub fn launch(console: &mut dyn Console) {
+    launch_with(console, storage::default_path().as_deref());
+}
+
+/// Manage a company, loading it from `autosave` at the start and saving it there after every
+/// change. Without a path nothing is kept. A file that fails to load is left alone and
+/// autosave stays off for the session.
+pub fn launch_with(console: &mut dyn Console, mut autosave: Option<&Path>) {
+    utils::clear_screen(console);
+    let mut company = Company::new();
+    writeln!(console, "Add or see employees to your company!");
+    if let Some(path) = autosave.filter(|path| path.exists()) {
+        match storage::load(path) {
+            Ok(loaded) => company = loaded,
+            Err(message) => {
+                writeln!(console, "{}", message);
+                writeln!(
+                    console,
+                    "Autosave is off for this session so {} is not overwritten",
+                    path.display()
+                );
+                writeln!(console, "Press enter to continue...");
+                utils::read_input(console);
+                autosave = None;
+            }
+        }
+    }
+    loop {
+        utils::clear_screen(console);
+        writeln!(console, "1. Add a new employee");
+        writeln!(console, "2. Search the company");
+        writeln!(console, "3. Remove an employee");
+        writeln!(console, "4. Move an employee to another department");
+        writeln!(console, "5. Rename a department");
+        writeln!(console, "6. Delete an empty department");
+        writeln!(console, "7. Save to a file");
+        writeln!(console, "8. Load from a file");
+        writeln!(console, "9. Import a CSV roster");
+        writeln!(console, "0. Back");
+
+        let Some(selection) = console.read_line() else {
+            break;
+        };
+
+        let selection = match selection.trim().parse::<u32>() {
+            Ok(num) => num,
+            Err(_) => {
+                writeln!(console, "Invalid selection. Please enter a valid number.");
+                continue;
+            }
+        };
+
+        let changed = match selection {
+            1 => add_employee(console, &mut company),
+            2 => {
+                search(console, &company);
+                continue;
+            }
+            3 => remove_employee(console, &mut company),
+            4 => move_employee(console, &mut company),
+            5 => rename_department(console, &mut company),
+            6 => delete_department(console, &mut company),
+            7 => save_to_file(console, &company),
+            8 => load_from_file(console, &mut company),
+            9 => import_roster(console, &mut company),
             0 => break,
             _ => {
-                println!("Invalid selection");
+                writeln!(console, "Invalid selection");
                 continue;
             }
+        };
+        if let Some(path) = autosave.filter(|_| changed) {
+            if let Err(message) = storage::save(&company, path) {
+                writeln!(console, "Autosave failed: {}", message);
+            }
         }
+        writeln!(console, "Press enter to continue...");
+        utils::read_input(console);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+
+    fn date(text: &str) -> NaiveDate {
+        parse_date(text).unwrap()
+    }
+
+    /// Sally (1) in Engineering, Amir (2) in Sales and Engineering reporting to Sally, and a
+    /// second Sam (3, 4) in each department.
+    fn sample() -> Company {
+        let mut company = Company::new();
+        let sally = Employee {
+            name: "Sally".to_string(),
+            title: "CTO".to_string(),
+            salary: 150_000,
+            hire_date: Some(date("2019-04-01")),
+            manager: None,
+        };
+        company.hire(sally, "Engineering").unwrap();
+        let amir = Employee {
+            manager: Some(1),
+            ..Employee::named("Amir")
+        };
+        company.hire(amir, "Sales").unwrap();
+        company.assign(2, "Engineering").unwrap();
+        company.hire(Employee::named("Sam"), "Engineering").unwrap();
+        company.hire(Employee::named("Sam"), "Sales").unwrap();
+        company
+    }
+
+    fn ids(employees: Option<Vec<(EmployeeId, &Employee)>>) -> Vec<EmployeeId> {
+        employees.unwrap().iter().map(|(id, _)| *id).collect()
+    }
+
+    #[test]
+    fn test_hire_and_lookups() {
+        let company = sample();
+        assert_eq!(company.len(), 4);
+        assert_eq!(company.employee(1).unwrap().title, "CTO");
+        assert!(company.employee(5).is_none());
+        let sams: Vec<EmployeeId> = company
+            .find_by_name(" sam ")
+            .iter()
+            .map(|(id, _)| *id)
+            .collect();
+        assert_eq!(sams, vec![3, 4]);
+        assert_eq!(ids(company.employees_in("Engineering")), vec![2, 1, 3]);
+        assert!(company.employees_in("Legal").is_none());
+        assert_eq!(company.departments(), vec!["Engineering", "Sales"]);
+        assert_eq!(company.departments_of(2), vec!["Engineering", "Sales"]);
+        assert_eq!(
+            company.describe(1),
+            "#1 Sally (CTO, salary 150000, hired 2019-04-01)"
+        );
+        assert_eq!(company.describe(2), "#2 Amir (reports to #1 Sally)");
+        assert_eq!(company.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_hire_validation() {
+        let mut company = sample();
+        assert!(company.hire(Employee::named(" "), "Sales").is_err());
+        assert!(company.hire(Employee::named("Kim"), "").is_err());
+        let orphan = Employee {
+            manager: Some(9),
+            ..Employee::named("Kim")
+        };
+        assert_eq!(
+            company.hire(orphan, "Sales"),
+            Err("Manager 9 not found".to_string())
+        );
+        assert_eq!(
+            company.assign(2, "Sales"),
+            Err("Employee 2 is already in Sales".to_string())
+        );
+        assert_eq!(company.hire(Employee::named("Kim"), "Sales"), Ok(5));
+        assert_eq!(
+            company.insert(EmployeeId::MAX, Employee::named("Max"), "Sales"),
+            Err(format!("Employee ID {} is too large", EmployeeId::MAX))
+        );
+        assert_eq!(company.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_update_rejects_management_loops() {
+        let mut company = sample();
+        let sally = Employee {
+            manager: Some(2),
+            ..company.employee(1).unwrap().clone()
+        };
+        assert_eq!(
+            company.update(1, sally),
+            Err("Employee 1 cannot manage themselves".to_string())
+        );
+        let promoted = Employee {
+            title: "Sales lead".to_string(),
+            ..company.employee(2).unwrap().clone()
+        };
+        assert_eq!(company.update(2, promoted), Ok(()));
+        assert_eq!(company.employee(2).unwrap().title, "Sales lead");
+        assert!(company.update(9, Employee::named("Nobody")).is_err());
+    }
+
+    #[test]
+    fn test_remove_employee() {
+        let mut company = sample();
+        assert_eq!(company.remove_employee(1).unwrap().name, "Sally");
+        assert_eq!(company.employee(2).unwrap().manager, None);
+        assert_eq!(ids(company.employees_in("Engineering")), vec![2, 3]);
+        assert!(company.remove_employee(1).is_err());
+        assert_eq!(company.hire(Employee::named("Zoe"), "Sales"), Ok(5));
+        assert_eq!(company.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_move_employee() {
+        let mut company = sample();
+        assert_eq!(company.move_employee(1, "Engineering", "Marketing"), Ok(()));
+        assert_eq!(ids(company.employees_in("Marketing")), vec![1]);
+        assert_eq!(ids(company.employees_in("Engineering")), vec![2, 3]);
+        assert_eq!(
+            company.move_employee(2, "Sales", "Engineering"),
+            Err("Employee 2 is already in Engineering".to_string())
+        );
+        assert_eq!(
+            company.move_employee(1, "Sales", "Engineering"),
+            Err("Employee 1 is not in Sales".to_string())
+        );
+        assert!(company.move_employee(9, "Sales", "Engineering").is_err());
+        assert_eq!(
+            company.move_employee(2, "Sales", ""),
+            Err("Department name cannot be empty".to_string())
+        );
+        assert_eq!(company.departments_of(2), vec!["Engineering", "Sales"]);
+        assert_eq!(company.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_rename_and_delete_department() {
+        let mut company = sample();
+        assert_eq!(company.rename_department("Engineering", "R&D"), Ok(()));
+        assert_eq!(ids(company.employees_in("R&D")), vec![2, 1, 3]);
+        assert_eq!(company.departments_of(2), vec!["R&D", "Sales"]);
+        assert!(company.rename_department("R&D", "Sales").is_err());
+        assert!(company.rename_department("Engineering", "Ops").is_err());
+        assert_eq!(
+            company.rename_department("R&D", ""),
+            Err("Department name cannot be empty".to_string())
+        );
+        assert_eq!(ids(company.employees_in("R&D")), vec![2, 1, 3]);
+        assert_eq!(company.check_invariants(), Ok(()));
+
+        assert_eq!(
+            company.delete_department("Sales"),
+            Err("Department Sales still has 2 employee(s)".to_string())
+        );
+        company.remove_employee(2).unwrap();
+        company.remove_employee(4).unwrap();
+        assert_eq!(company.delete_department("Sales"), Ok(()));
+        assert!(company.delete_department("Sales").is_err());
+        assert_eq!(company.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_check_invariants() {
+        let mut company = sample();
+        company
+            .department_employee
+            .get_mut("Sales")
+            .unwrap()
+            .remove(&2);
+        assert_eq!(
+            company.check_invariants(),
+            Err("Employee 2 is in Sales but not listed there".to_string())
+        );
+
+        let mut company = sample();
+        company.employee_department.remove(&3);
+        assert_eq!(
+            company.check_invariants(),
+            Err("Employee 3 has no department".to_string())
+        );
+
+        let mut company = sample();
+        company
+            .department_employee
+            .get_mut("Sales")
+            .unwrap()
+            .insert(3);
+        assert_eq!(
+            company.check_invariants(),
+            Err("Department Sales lists employee 3 who is not in it".to_string())
+        );
+
+        let mut company = sample();
+        company.employees.get_mut(&1).unwrap().manager = Some(2);
+        assert_eq!(
+            company.check_invariants(),
+            Err("Employee 1 cannot manage themselves".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_employee() {
+        let company = sample();
+        assert_eq!(resolve_employee(&company, "amir"), Ok(2));
+        assert_eq!(resolve_employee(&company, "#4"), Ok(4));
+        assert_eq!(
+            resolve_employee(&company, "Sam"),
+            Err(
+                "Several employees are called Sam, enter an ID instead: #3 Sam; #4 Sam".to_string()
+            )
+        );
+        assert!(resolve_employee(&company, "9").is_err());
+        assert!(resolve_employee(&company, "Zoe").is_err());
+    }
+
+    #[test]
+    fn test_launch() {
+        let steps: [&[&str]; 8] = [
+            &[
+                "1",
+                "Sally",
+                "Engineering",
+                "CTO",
+                "lots",
+                "150000",
+                "2019-04-01",
+                "",
+                "",
+            ],
+            &["1", "Sally", "Sales", "", "", "", "sally", ""],
+            &["4", "Sally", ""],
+            &["4", "#2", "Sales", "Marketing", ""],
+            &["5", "Marketing", "Growth", ""],
+            &["6", "Sales", ""],
+            &["2", "3", "sally", "", "0"],
+            &["0"],
+        ];
+        let mut console = ScriptedConsole::new(steps.concat());
+        launch_with(&mut console, None);
+        let output = console.output();
+        assert!(output.contains("\"lots\" is not a valid salary"));
+        assert!(output.contains("Added #1 Sally (CTO, salary 150000, hired 2019-04-01)\n"));
+        assert!(output.contains("Added #2 Sally (reports to #1 Sally)\n"));
+        assert!(output.contains("Several employees are called Sally"));
+        assert!(output.contains("Department deleted"));
+        assert!(output.contains("#2 Sally (reports to #1 Sally) in Growth\n"));
+        assert_eq!(console.remaining_input(), 0);
+    }
+
+    #[test]
+    fn test_autosave() {
+        let dir = std::env::temp_dir().join(format!("autosave_test_{}", std::process::id()));
+        let path = dir.join("company.json");
+        let roster = dir.join("roster.csv");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&roster, "Sales,Zoe\nSales\n").unwrap();
+
+        let steps: [&[&str]; 3] = [
+            &["1", "Sally", "Engineering", "", "", "", "", ""],
+            &["9", roster.to_str().unwrap(), ""],
+            &["0"],
+        ];
+        let mut console = ScriptedConsole::new(steps.concat());
+        launch_with(&mut console, Some(&path));
+        assert!(console
+            .output()
+            .contains("Imported 1 rows\n  line 2: expected 2 fields"));
+
+        let mut console = ScriptedConsole::new(["2", "2", "Sales", "", "0", "0"]);
+        launch_with(&mut console, Some(&path));
+        assert!(console.output().contains("#2 Zoe\n"));
+        assert_eq!(console.remaining_input(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_autosave_is_kept() {
+        let dir = std::env::temp_dir().join(format!("corrupt_test_{}", std::process::id()));
+        let path = dir.join("company.json");
+        fs::create_dir_all(&dir).unwrap();
+        let corrupt = "{\"teams\": {}}";
+        fs::write(&path, corrupt).unwrap();
+
+        let steps: [&[&str]; 3] = [&[""], &["1", "Kim", "Ops", "", "", "", "", ""], &["0"]];
+        let mut console = ScriptedConsole::new(steps.concat());
+        launch_with(&mut console, Some(&path));
+        assert!(console.output().contains("Invalid company file"));
+        assert!(console
+            .output()
+            .contains("Autosave is off for this session"));
+        assert!(console.output().contains("Added #1 Kim"));
+        assert_eq!(console.remaining_input(), 0);
+        assert_eq!(fs::read_to_string(&path).unwrap(), corrupt);
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
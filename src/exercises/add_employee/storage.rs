@@ -0,0 +1,683 @@
+use super::{parse_date, parse_salary, Company, Employee, EmployeeId};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The file formats a company can be kept in, chosen by file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `{"next_id": 3, "employees": [{"id": 1, "name": "Sally", ...}], "departments": {"Sales": [1]}}`
+    Json,
+    /// One row per employee and department with the columns of [`COLUMNS`], a row with only
+    /// the department for an empty department, and a last row with only the next ID.
+    Csv,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Result<Format, String> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("json") => Ok(Format::Json),
+            Some("csv") => Ok(Format::Csv),
+            _ => Err(format!(
+                "Cannot tell the format of {}, use a .json or .csv file",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// `company.json` in the `guessing_game` folder of the user's data directory.
+pub fn default_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("guessing_game").join("company.json"))
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Snapshot {
+    #[serde(default)]
+    next_id: Option<EmployeeId>,
+    #[serde(default)]
+    employees: Vec<Record>,
+    departments: BTreeMap<String, Vec<EmployeeId>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Record {
+    id: EmployeeId,
+    name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    title: String,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    salary: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hire_date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    manager: Option<EmployeeId>,
+}
+
+fn is_zero(salary: &u64) -> bool {
+    *salary == 0
+}
+
+pub fn to_json(company: &Company) -> String {
+    let employees = company
+        .employees()
+        .map(|(id, employee)| Record {
+            id,
+            name: employee.name.clone(),
+            title: employee.title.clone(),
+            salary: employee.salary,
+            hire_date: employee.hire_date,
+            manager: employee.manager,
+        })
+        .collect();
+    let departments = company
+        .departments()
+        .into_iter()
+        .map(|department| {
+            let members = company
+                .employees_in(department)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect();
+            (department.to_string(), members)
+        })
+        .collect();
+    let snapshot = Snapshot {
+        next_id: Some(company.next_id),
+        employees,
+        departments,
+    };
+    serde_json::to_string_pretty(&snapshot).expect("a snapshot always serializes")
+}
+
+pub fn from_json(text: &str) -> Result<Company, String> {
+    let invalid = |message: String| format!("Invalid company file: {}", message);
+    let snapshot: Snapshot = serde_json::from_str(text).map_err(|err| invalid(err.to_string()))?;
+    let mut company = Company::new();
+    for record in snapshot.employees {
+        let employee = Employee {
+            name: record.name.trim().to_string(),
+            title: record.title.trim().to_string(),
+            salary: record.salary,
+            hire_date: record.hire_date,
+            manager: record.manager,
+        };
+        if company.employees.insert(record.id, employee).is_some() {
+            return Err(invalid(format!("employee ID {} is used twice", record.id)));
+        }
+        let next_id = record
+            .id
+            .checked_add(1)
+            .ok_or_else(|| invalid(format!("employee ID {} is too large", record.id)))?;
+        company.next_id = company.next_id.max(next_id);
+    }
+    company.next_id = company.next_id.max(snapshot.next_id.unwrap_or_default());
+
+    for (department, members) in snapshot.departments {
+        let department = department.trim();
+        if department.is_empty() {
+            return Err(invalid("a department has no name".to_string()));
+        }
+        company.add_department(department);
+        for id in members {
+            if !company.employees.contains_key(&id) {
+                return Err(invalid(format!(
+                    "{} lists employee {} who does not exist",
+                    department, id
+                )));
+            }
+            company
+                .employee_department
+                .entry(id)
+                .or_default()
+                .insert(department.to_string());
+            company
+                .department_employee
+                .entry(department.to_string())
+                .or_default()
+                .insert(id);
+        }
+    }
+    company.check_invariants().map_err(invalid)?;
+    Ok(company)
+}
+
+/// The columns `to_csv` writes, which `import_csv` also understands in any order.
+pub const COLUMNS: [&str; 7] = [
+    "department",
+    "id",
+    "name",
+    "title",
+    "salary",
+    "hire_date",
+    "manager_id",
+];
+
+/// The columns of a roster without a header row.
+const ROSTER_COLUMNS: [&str; 2] = ["department", "name"];
+
+pub fn to_csv(company: &Company) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut write = |record: [&str; 7]| {
+        writer
+            .write_record(record)
+            .expect("writing to memory does not fail");
+    };
+    write(COLUMNS);
+    for department in company.departments() {
+        let employees = company.employees_in(department).unwrap_or_default();
+        if employees.is_empty() {
+            write([department, "", "", "", "", "", ""]);
+        }
+        for (id, employee) in employees {
+            let salary = match employee.salary {
+                0 => String::new(),
+                salary => salary.to_string(),
+            };
+            let hire_date = employee
+                .hire_date
+                .map(|date| date.to_string())
+                .unwrap_or_default();
+            let manager = employee
+                .manager
+                .map(|id| id.to_string())
+                .unwrap_or_default();
+            write([
+                department,
+                &id.to_string(),
+                &employee.name,
+                &employee.title,
+                &salary,
+                &hire_date,
+                &manager,
+            ]);
+        }
+    }
+    write(["", &company.next_id.to_string(), "", "", "", "", ""]);
+    let bytes = writer
+        .into_inner()
+        .expect("writing to memory does not fail");
+    String::from_utf8(bytes).expect("the records are UTF-8")
+}
+
+/// A CSV row that could not be imported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    pub line: u64,
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// The outcome of importing a CSV roster.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Import {
+    /// Rows that added an employee or a department, or put an employee in one more department.
+    pub added: usize,
+    pub errors: Vec<RowError>,
+}
+
+/// The line a record starts on. The reader's own line count includes blank lines skipped
+/// before the record, so it is worked out from the byte offset instead.
+fn line_at(text: &str, position: &csv::Position) -> u64 {
+    let start = (position.byte() as usize).min(text.len());
+    let blank = text[start..].len() - text[start..].trim_start_matches(['\r', '\n']).len();
+    let before = &text[..start + blank];
+    before.matches('\n').count() as u64 + 1
+}
+
+/// "a, b and c"
+fn list(columns: &[String]) -> String {
+    match columns {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+fn parse_id(text: &str) -> Result<Option<EmployeeId>, String> {
+    if text.is_empty() {
+        return Ok(None);
+    }
+    text.trim_start_matches('#')
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("\"{}\" is not a valid employee ID", text))
+}
+
+/// Imports rows one at a time, remembering who the IDs and names of the file refer to.
+struct Importer<'a> {
+    company: &'a mut Company,
+    /// IDs below this were handed out before the import and are never reused.
+    first_free: EmployeeId,
+    /// Company IDs of the employees added under each ID of the file.
+    ids: HashMap<EmployeeId, EmployeeId>,
+    /// Company IDs of the employees added by name only.
+    names: HashMap<String, EmployeeId>,
+    /// Line, employee and manager ID in the file, resolved once every row is in.
+    managers: Vec<(u64, EmployeeId, EmployeeId)>,
+}
+
+impl Importer<'_> {
+    /// Import one row. Returns whether it added anything, which the next ID row does not.
+    fn row<'r>(&mut self, line: u64, field: impl Fn(&str) -> &'r str) -> Result<bool, String> {
+        let department = field("department");
+        let name = field("name");
+        let file_id = parse_id(field("id"))?;
+        if department.is_empty() {
+            return match file_id {
+                Some(next_id) if name.is_empty() => {
+                    // Only ever raised, so no ID in use is handed out again
+                    self.company.next_id = self.company.next_id.max(next_id);
+                    Ok(false)
+                }
+                _ => Err("missing department".to_string()),
+            };
+        }
+        if name.is_empty() && file_id.is_none() {
+            return match self.company.add_department(department) {
+                true => Ok(true),
+                false => Err(format!("department {} already exists", department)),
+            };
+        }
+
+        let label = match file_id {
+            Some(id) if name.is_empty() => format!("#{}", id),
+            _ => name.to_string(),
+        };
+        let already_in = || format!("{} is already in {}", label, department);
+        let known = match file_id {
+            Some(id) => self.ids.get(&id),
+            None => self.names.get(name),
+        };
+        if let Some(&id) = known {
+            return self
+                .company
+                .assign(id, department)
+                .map(|()| true)
+                .map_err(|_| already_in());
+        }
+        if file_id.is_none() {
+            let listed = self.company.employees_in(department).unwrap_or_default();
+            if listed.iter().any(|(_, employee)| employee.name == name) {
+                return Err(already_in());
+            }
+        }
+
+        let salary = match field("salary") {
+            "" => 0,
+            salary => parse_salary(salary)?,
+        };
+        let hire_date = match field("hire_date") {
+            "" => None,
+            date => Some(parse_date(date)?),
+        };
+        let manager = parse_id(field("manager_id"))?;
+        let employee = Employee {
+            name: name.to_string(),
+            title: field("title").to_string(),
+            salary,
+            hire_date,
+            manager: None,
+        };
+        // IDs from the file are kept unless that would reuse one the company handed out
+        let free = |id: EmployeeId| id >= self.first_free && self.company.employee(id).is_none();
+        let id = match file_id.filter(|&id| free(id)) {
+            Some(id) => {
+                self.company.insert(id, employee, department)?;
+                id
+            }
+            None => self.company.hire(employee, department)?,
+        };
+        match file_id {
+            Some(file_id) => self.ids.insert(file_id, id),
+            None => self.names.insert(name.to_string(), id),
+        };
+        if let Some(manager) = manager {
+            self.managers.push((line, id, manager));
+        }
+        Ok(true)
+    }
+
+    /// Set the managers, now that every employee they might refer to is in.
+    fn resolve_managers(&mut self) -> Vec<RowError> {
+        let mut errors = Vec::new();
+        for (line, id, manager) in std::mem::take(&mut self.managers) {
+            // A manager missing from the file may already be in the company
+            let resolved = self
+                .ids
+                .get(&manager)
+                .copied()
+                .or_else(|| self.company.employee(manager).is_some().then_some(manager));
+            let result = match resolved {
+                Some(resolved) => {
+                    let employee = Employee {
+                        manager: Some(resolved),
+                        ..self.company.employee(id).cloned().unwrap_or_default()
+                    };
+                    self.company.update(id, employee)
+                }
+                None => Err(format!("manager #{} not found", manager)),
+            };
+            if let Err(message) = result {
+                errors.push(RowError { line, message });
+            }
+        }
+        errors
+    }
+}
+
+/// Add the rows of a CSV roster to `company`. A header row naming at least the department
+/// and name columns picks the columns of [`COLUMNS`] to read; without one, rows are
+/// `department,name`. A row with only an ID raises the next ID to it. Blank lines are
+/// ignored. Rows that cannot be used are reported and the others are still imported.
+pub fn import_csv(company: &mut Company, text: &str) -> Import {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut columns: Vec<String> = ROSTER_COLUMNS.map(String::from).to_vec();
+    let mut importer = Importer {
+        first_free: company.next_id,
+        company,
+        ids: HashMap::new(),
+        names: HashMap::new(),
+        managers: Vec::new(),
+    };
+    let mut import = Import::default();
+    for (index, record) in reader.records().enumerate() {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                let line = err.position().map_or(0, |position| line_at(text, position));
+                import.errors.push(RowError {
+                    line,
+                    message: err.to_string(),
+                });
+                continue;
+            }
+        };
+        let line = record
+            .position()
+            .map_or(0, |position| line_at(text, position));
+        let fields: Vec<&str> = record.iter().map(str::trim).collect();
+        if index == 0 {
+            let header: Vec<String> = fields.iter().map(|field| field.to_lowercase()).collect();
+            if ROSTER_COLUMNS
+                .iter()
+                .all(|column| header.iter().any(|h| h == column))
+            {
+                columns = header;
+                continue;
+            }
+        }
+        if fields.len() != columns.len() {
+            import.errors.push(RowError {
+                line,
+                message: format!(
+                    "expected {} fields, {}, found {}",
+                    columns.len(),
+                    list(&columns),
+                    fields.len()
+                ),
+            });
+            continue;
+        }
+        let field = |column: &str| {
+            columns
+                .iter()
+                .position(|c| c == column)
+                .map_or("", |i| fields[i])
+        };
+        match importer.row(line, field) {
+            Ok(added) => import.added += usize::from(added),
+            Err(message) => import.errors.push(RowError { line, message }),
+        }
+    }
+    import.errors.extend(importer.resolve_managers());
+    import.errors.sort_by_key(|error| error.line);
+    import
+}
+
+/// Read a CSV roster as a whole company, failing on the first unusable row.
+pub fn from_csv(text: &str) -> Result<Company, String> {
+    let mut company = Company::new();
+    let import = import_csv(&mut company, text);
+    match import.errors.first() {
+        Some(error) => Err(format!("Invalid company file: {}", error)),
+        None => Ok(company),
+    }
+}
+
+/// Load a company saved with `save`.
+pub fn load(path: &Path) -> Result<Company, String> {
+    let format = Format::from_path(path)?;
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+    match format {
+        Format::Json => from_json(&text),
+        Format::Csv => from_csv(&text),
+    }
+    .map_err(|message| format!("{}: {}", path.display(), message))
+}
+
+/// Write the company to `path`, sorted by department and name, creating its folder if needed.
+pub fn save(company: &Company, path: &Path) -> Result<(), String> {
+    let contents = match Format::from_path(path)? {
+        Format::Json => to_json(company),
+        Format::Csv => to_csv(company),
+    };
+    let write = || -> std::io::Result<()> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        // Write next to the real file first so a crash never leaves it half written.
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, contents)?;
+        fs::rename(&temp_path, path)
+    };
+    write().map_err(|err| format!("Could not write {}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("company_test_{}", std::process::id()))
+            .join(name)
+    }
+
+    /// Sally (1) in Engineering, "Amir, Jr." (2) in Sales and Engineering reporting to Sally,
+    /// an empty Legal department, and a removed employee so the next ID is 4.
+    fn sample() -> Company {
+        let mut company = Company::new();
+        let sally = Employee {
+            title: "CTO".to_string(),
+            salary: 150_000,
+            hire_date: Some(parse_date("2019-04-01").unwrap()),
+            ..Employee::named("Sally")
+        };
+        company.hire(sally, "Engineering").unwrap();
+        let amir = Employee {
+            manager: Some(1),
+            ..Employee::named("Amir, Jr.")
+        };
+        company.hire(amir, "Sales").unwrap();
+        company.assign(2, "Engineering").unwrap();
+        company.add_department("Legal");
+        let temp = company.hire(Employee::named("Temp"), "Legal").unwrap();
+        company.remove_employee(temp).unwrap();
+        company
+    }
+
+    /// Every department with the IDs and records of its employees.
+    fn contents(company: &Company) -> Vec<(String, Vec<(EmployeeId, Employee)>)> {
+        company
+            .departments()
+            .into_iter()
+            .map(|department| {
+                let employees = company.employees_in(department).unwrap();
+                let employees = employees
+                    .into_iter()
+                    .map(|(id, employee)| (id, employee.clone()))
+                    .collect();
+                (department.to_string(), employees)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(Format::from_path(Path::new("a/b.JSON")), Ok(Format::Json));
+        assert_eq!(Format::from_path(Path::new("b.csv")), Ok(Format::Csv));
+        assert!(Format::from_path(Path::new("b.txt")).is_err());
+        assert!(Format::from_path(Path::new("csv")).is_err());
+    }
+
+    #[test]
+    fn test_csv_is_sorted() {
+        assert_eq!(
+            to_csv(&sample()),
+            "department,id,name,title,salary,hire_date,manager_id\n\
+             Engineering,2,\"Amir, Jr.\",,,,1\n\
+             Engineering,1,Sally,CTO,150000,2019-04-01,\n\
+             Legal,,,,,,\n\
+             Sales,2,\"Amir, Jr.\",,,,1\n\
+             ,4,,,,,\n"
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let json = to_json(&sample());
+        assert!(json.find("Engineering") < json.find("Legal"));
+        let company = from_json(&json).unwrap();
+        assert_eq!(contents(&company), contents(&sample()));
+        assert_eq!(company.next_id, 4);
+        assert_eq!(company.check_invariants(), Ok(()));
+        assert!(from_json("{\"departments\": {\"\": []}}").is_err());
+        assert!(from_json("{\"departments\": {\"Sales\": [\"Sally\"]}}").is_err());
+        assert!(from_json("{\"departments\": {\"Sales\": [1]}}").is_err());
+        assert!(from_json("{\"teams\": {}}").is_err());
+        assert_eq!(
+            from_json("{\"employees\": [{\"id\": 1, \"name\": \"Sally\"}], \"departments\": {}}")
+                .unwrap_err(),
+            "Invalid company file: Employee 1 has no department"
+        );
+    }
+
+    #[test]
+    fn test_import_reports_bad_rows() {
+        let mut company = sample();
+        let roster =
+            "department,name\nSales,Zoe\n\n,Nobody\nSales,Zoe\nOps,Kim,extra\nOps\nSupport,\n";
+        let import = import_csv(&mut company, roster);
+        assert_eq!(import.added, 2);
+        let errors: Vec<String> = import.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "line 4: missing department",
+                "line 5: Zoe is already in Sales",
+                "line 6: expected 2 fields, department and name, found 3",
+                "line 7: expected 2 fields, department and name, found 1",
+            ]
+        );
+        assert_eq!(company.employees_in("Support").unwrap().len(), 0);
+        assert_eq!(company.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_largest_id_is_rejected() {
+        let json = "{\"employees\": [{\"id\": 4294967295, \"name\": \"Max\"}], \
+                    \"departments\": {\"Ops\": [4294967295]}}";
+        assert_eq!(
+            from_json(json).unwrap_err(),
+            "Invalid company file: employee ID 4294967295 is too large"
+        );
+
+        let mut company = Company::new();
+        let import = import_csv(&mut company, "department,id,name\nOps,4294967295,Max\n");
+        assert_eq!(
+            import.errors[0].to_string(),
+            "line 2: Employee ID 4294967295 is too large"
+        );
+        assert!(company.is_empty());
+    }
+
+    #[test]
+    fn test_import_with_ids_and_managers() {
+        let mut company = sample();
+        let roster = "name,department,id,manager_id,hire_date,salary\n\
+                      Kim,Ops,10,11,,\n\
+                      Lee,Ops,11,1,2022-02-30,\n\
+                      Lee,Ops,11,1,2022-02-28,90000\n\
+                      Lee,Sales,11,,,\n\
+                      Ann,Ops,2,13,,\n\
+                      Bo,Ops,x,,,\n";
+        let import = import_csv(&mut company, roster);
+        assert_eq!(import.added, 4);
+        let errors: Vec<String> = import.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "line 3: \"2022-02-30\" is not a valid date, use YYYY-MM-DD",
+                "line 6: manager #13 not found",
+                "line 7: \"x\" is not a valid employee ID",
+            ]
+        );
+        // ID 10 and 11 were free, 2 was taken so Ann got the next one
+        assert_eq!(company.describe(10), "#10 Kim (reports to #11 Lee)");
+        assert_eq!(
+            company.describe(11),
+            "#11 Lee (salary 90000, hired 2022-02-28, reports to #1 Sally)"
+        );
+        assert_eq!(company.departments_of(11), vec!["Ops", "Sales"]);
+        assert_eq!(company.find_by_name("Ann")[0].0, 12);
+        assert_eq!(company.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        for name in ["company.json", "company.csv"] {
+            let path = temp_path(name);
+            save(&sample(), &path).unwrap();
+            let company = load(&path).unwrap();
+            assert_eq!(contents(&company), contents(&sample()));
+            assert_eq!(company.next_id, 4);
+            fs::remove_file(&path).unwrap();
+        }
+        assert!(load(&temp_path("missing.json")).is_err());
+        assert!(save(&sample(), &temp_path("company.txt")).is_err());
+
+        let path = temp_path("broken.csv");
+        fs::write(&path, "Sales,Zoe\nSales,Zoe\n").unwrap();
+        assert_eq!(
+            load(&path).unwrap_err(),
+            format!(
+                "{}: Invalid company file: line 2: Zoe is already in Sales",
+                path.display()
+            )
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,47 +1,90 @@
+pub mod running;
+pub mod selection;
+
+use super::statistics;
+use crate::console::Console;
+use crate::menu::Menu;
 use crate::utils;
+use selection::Algorithm;
+use std::num::ParseIntError;
 
-fn find_median(arr: Vec<i32>) -> f64 {
-    let mut arr = arr;
-    arr.sort();
-    let len = arr.len();
-    if len % 2 == 0 {
-        let mid = len / 2;
-        (arr[mid - 1] + arr[mid]) as f64 / 2.0
-    } else {
-        arr[len / 2] as f64
-    }
+/// Median of a non-empty list.
+pub fn find_median(arr: Vec<i32>) -> f64 {
+    statistics::median(&arr).expect("the list is not empty")
+}
+
+/// Parse a comma separated list of integers.
+pub fn parse_list(input: &str) -> Result<Vec<i32>, ParseIntError> {
+    input.split(',').map(|x| x.trim().parse::<i32>()).collect()
 }
 
-pub fn launch() {
-    utils::clear_screen();
-    println!("Find Median");
+pub fn launch(console: &mut dyn Console) {
+    launch_with(console, Algorithm::Sort);
+}
+
+/// Find medians, selecting the middle values with `algorithm`.
+pub fn launch_with(console: &mut dyn Console, algorithm: Algorithm) {
+    utils::clear_screen(console);
+    writeln!(console, "Find Median ({})", algorithm.name());
     loop {
-        println!(
-        "Enter a list of integers separated by commas, or just press Enter to return to the main menu"
-    );
+        writeln!(
+            console,
+            "Enter a list of integers separated by commas, or just press Enter to return to the main menu"
+        );
 
-        let input = utils::read_input();
+        let input = utils::read_input(console);
 
         if input.trim().is_empty() {
             break;
         }
 
-        let arr_result: Result<Vec<i32>, _> =
-            input.split(',').map(|x| x.trim().parse::<i32>()).collect();
-
-        match arr_result {
+        match parse_list(&input) {
             Ok(arr) => {
                 if arr.is_empty() {
-                    println!("You entered an empty list. Try again.");
+                    writeln!(console, "You entered an empty list. Try again.");
                     continue;
                 }
-                println!("Array: {:?}", arr);
-                println!("Median: {}", find_median(arr));
+                writeln!(console, "Array: {:?}", arr);
+                let median = selection::median(&arr, algorithm).expect("the list is not empty");
+                writeln!(console, "Median: {}", median);
             }
             Err(_) => {
-                println!("Invalid input. Please enter a valid list of integers.");
+                writeln!(
+                    console,
+                    "Invalid input. Please enter a valid list of integers."
+                );
                 continue;
             }
         }
     }
 }
+
+pub fn menu() -> Menu<'static> {
+    let mut menu = Menu::new("Find Median");
+    for algorithm in Algorithm::ALL {
+        menu = menu.entry(
+            &format!("Find the median ({})", algorithm.name()),
+            move |console| launch_with(console, algorithm),
+        );
+    }
+    menu.entry("Benchmark the algorithms", selection::launch_benchmark)
+        .entry("Running median", running::launch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_median() {
+        assert_eq!(find_median(vec![3, 1, 2]), 2.0);
+        assert_eq!(find_median(vec![4, 1, 3, 2]), 2.5);
+        assert_eq!(find_median(vec![i32::MAX, i32::MAX - 1]), 2147483646.5);
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(parse_list("3, 1,2"), Ok(vec![3, 1, 2]));
+        assert!(parse_list("3,x").is_err());
+    }
+}
@@ -0,0 +1,306 @@
+use crate::console::Console;
+use crate::dsa::heap::{Heap, HeapType};
+use crate::utils;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+
+/// Median of a stream of numbers, updated as each one arrives.
+///
+/// The smaller half of the values lives in a max heap and the larger half in a min heap, so the
+/// median is always at the top of one or both. With a window, only the latest values count:
+/// expired values are remembered in `expired` and discarded once they reach the top of a heap,
+/// since a heap can only remove its top.
+pub struct RunningMedian {
+    lower: Heap<i64>,
+    upper: Heap<i64>,
+    // Live values in each heap, not counting expired ones still stored there
+    lower_len: usize,
+    upper_len: usize,
+    window: Option<usize>,
+    recent: VecDeque<i64>,
+    expired: HashMap<i64, usize>,
+}
+
+impl Default for RunningMedian {
+    fn default() -> Self {
+        RunningMedian::new()
+    }
+}
+
+impl RunningMedian {
+    /// Median of every value pushed so far.
+    pub fn new() -> Self {
+        RunningMedian {
+            lower: Heap::new(HeapType::MaxHeap),
+            upper: Heap::new(HeapType::MinHeap),
+            lower_len: 0,
+            upper_len: 0,
+            window: None,
+            recent: VecDeque::new(),
+            expired: HashMap::new(),
+        }
+    }
+
+    /// Median of the last `size` values pushed.
+    pub fn with_window(size: usize) -> Self {
+        RunningMedian {
+            window: Some(size.max(1)),
+            ..RunningMedian::new()
+        }
+    }
+
+    /// Number of values the median is currently taken over.
+    pub fn len(&self) -> usize {
+        self.lower_len + self.upper_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add `value`, dropping the oldest value if the window is full, and return the new median.
+    pub fn push(&mut self, value: i64) -> f64 {
+        match self.lower.peek() {
+            Some(&top) if value > top => {
+                self.upper.push(value);
+                self.upper_len += 1;
+            }
+            _ => {
+                self.lower.push(value);
+                self.lower_len += 1;
+            }
+        }
+        if let Some(window) = self.window {
+            self.recent.push_back(value);
+            if self.recent.len() > window {
+                let oldest = self.recent.pop_front().expect("the window is not empty");
+                self.expire(oldest);
+            }
+        }
+        self.rebalance();
+        self.median().expect("a value was just pushed")
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        let lower = *self.lower.peek()? as f64;
+        if self.lower_len > self.upper_len {
+            return Some(lower);
+        }
+        let upper = *self.upper.peek()? as f64;
+        Some(lower + (upper - lower) / 2.0)
+    }
+
+    fn expire(&mut self, value: i64) {
+        *self.expired.entry(value).or_insert(0) += 1;
+        // Equal values are interchangeable, so one at the lower top may stand in for it
+        if self.lower.peek().is_some_and(|&top| value <= top) {
+            self.lower_len -= 1;
+            Self::prune(&mut self.lower, &mut self.expired);
+        } else {
+            self.upper_len -= 1;
+            Self::prune(&mut self.upper, &mut self.expired);
+        }
+    }
+
+    /// Pop expired values off the top of `heap`.
+    fn prune(heap: &mut Heap<i64>, expired: &mut HashMap<i64, usize>) {
+        while let Some(top) = heap.peek() {
+            match expired.get_mut(top) {
+                Some(count) => {
+                    *count -= 1;
+                    if *count == 0 {
+                        expired.remove(top);
+                    }
+                    heap.pop();
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Keep the lower half the same size as the upper half, or one larger.
+    fn rebalance(&mut self) {
+        if self.lower_len > self.upper_len + 1 {
+            let value = self.lower.pop().expect("the lower half is not empty");
+            self.upper.push(value);
+            self.lower_len -= 1;
+            self.upper_len += 1;
+            Self::prune(&mut self.lower, &mut self.expired);
+        } else if self.lower_len < self.upper_len {
+            let value = self.upper.pop().expect("the upper half is not empty");
+            self.lower.push(value);
+            self.upper_len -= 1;
+            self.lower_len += 1;
+            Self::prune(&mut self.upper, &mut self.expired);
+        }
+    }
+}
+
+/// Push every number on `line`, separated by commas or whitespace, reporting the median after
+/// each. Stops at the first invalid number.
+pub fn feed(
+    console: &mut dyn Console,
+    median: &mut RunningMedian,
+    line: &str,
+) -> Result<(), String> {
+    for item in line
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|item| !item.is_empty())
+    {
+        let value = item
+            .parse::<i64>()
+            .map_err(|_| format!("\"{}\" is not a valid integer", item))?;
+        let current = median.push(value);
+        writeln!(console, "{} -> median {}", value, current);
+    }
+    Ok(())
+}
+
+/// Feed every line of the file at `path`.
+pub fn feed_file(
+    console: &mut dyn Console,
+    median: &mut RunningMedian,
+    path: &str,
+) -> Result<(), String> {
+    let text =
+        fs::read_to_string(path).map_err(|err| format!("Could not read {}: {}", path, err))?;
+    for (number, line) in text.lines().enumerate() {
+        feed(console, median, line)
+            .map_err(|message| format!("{}:{}: {}", path, number + 1, message))?;
+    }
+    Ok(())
+}
+
+/// Parse a window size. An empty answer means no window.
+pub fn parse_window(text: &str) -> Result<Option<usize>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    match text.parse::<usize>() {
+        Ok(size) if size > 0 => Ok(Some(size)),
+        _ => Err(format!("\"{}\" is not a valid window size", text)),
+    }
+}
+
+pub fn launch(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    writeln!(console, "Running Median");
+    let window = loop {
+        writeln!(
+            console,
+            "Enter a window size, or press Enter to use every number:"
+        );
+        let Some(input) = console.read_line() else {
+            return;
+        };
+        match parse_window(&input) {
+            Ok(window) => break window,
+            Err(message) => writeln!(console, "{}", message),
+        }
+    };
+    let mut median = match window {
+        Some(size) => RunningMedian::with_window(size),
+        None => RunningMedian::new(),
+    };
+    loop {
+        writeln!(
+            console,
+            "Enter integers, \"file <path>\" to read them from a file, or press Enter to exit:"
+        );
+        let input = utils::read_input(console);
+        if input.is_empty() {
+            break;
+        }
+        let result = match input.strip_prefix("file ") {
+            Some(path) => feed_file(console, &mut median, path.trim()),
+            None => feed(console, &mut median, &input),
+        };
+        if let Err(message) = result {
+            writeln!(console, "{}", message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+    use crate::exercises::statistics;
+    use crate::random;
+    use rand::Rng;
+
+    #[test]
+    fn test_running_median() {
+        let mut median = RunningMedian::new();
+        assert_eq!(median.median(), None);
+        let medians: Vec<f64> = [5, 15, 1, 3, 8].iter().map(|&v| median.push(v)).collect();
+        assert_eq!(medians, vec![5.0, 10.0, 5.0, 4.0, 5.0]);
+        assert_eq!(median.len(), 5);
+    }
+
+    #[test]
+    fn test_no_overflow() {
+        let mut median = RunningMedian::new();
+        median.push(i64::MAX);
+        assert_eq!(median.push(i64::MAX), i64::MAX as f64);
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        let mut median = RunningMedian::with_window(3);
+        let medians: Vec<f64> = [1, 3, -1, -3, 5, 3, 6, 7]
+            .iter()
+            .map(|&v| median.push(v))
+            .collect();
+        assert_eq!(medians, vec![1.0, 2.0, 1.0, -1.0, -1.0, 3.0, 5.0, 6.0]);
+        assert_eq!(median.len(), 3);
+    }
+
+    #[test]
+    fn test_sliding_window_matches_recomputing() {
+        random::seed(18);
+        let values: Vec<i64> =
+            random::with_rng(|rng| (0..500).map(|_| rng.gen_range(-20..20)).collect());
+        for window in [1, 2, 5, 16] {
+            let mut median = RunningMedian::with_window(window);
+            for (i, &value) in values.iter().enumerate() {
+                let start = (i + 1).saturating_sub(window);
+                assert_eq!(
+                    Some(median.push(value)),
+                    statistics::median(&values[start..=i]),
+                    "window {} at {}",
+                    window,
+                    i
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_feed_and_files() {
+        let path = std::env::temp_dir().join(format!("running_median_{}.txt", std::process::id()));
+        fs::write(&path, "4\n2 6\n\nx\n").unwrap();
+        let mut console = ScriptedConsole::default();
+        let mut median = RunningMedian::new();
+        let result = feed_file(&mut console, &mut median, path.to_str().unwrap());
+        assert_eq!(
+            console.output(),
+            "4 -> median 4\n2 -> median 3\n6 -> median 4\n"
+        );
+        assert!(result
+            .unwrap_err()
+            .ends_with(":4: \"x\" is not a valid integer"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_session() {
+        let mut console = ScriptedConsole::new(["0", "2", "1, 2", "9", ""]);
+        launch(&mut console);
+        assert!(console
+            .output()
+            .contains("\"0\" is not a valid window size"));
+        assert!(console.output().contains("9 -> median 5.5\n"));
+    }
+}
@@ -0,0 +1,436 @@
+use crate::console::Console;
+use crate::exercises::statistics::Sample;
+use crate::random;
+use crate::utils;
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_BENCHMARK_SIZE: usize = 100_000;
+pub const MAX_BENCHMARK_SIZE: usize = 5_000_000;
+/// Largest adversarial input the benchmark builds. Building it takes as long as the
+/// quadratic run it provokes.
+pub const MAX_ADVERSARIAL_SIZE: usize = 10_000;
+
+/// How to find the kth smallest value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Sort everything, O(n log n).
+    Sort,
+    /// Partition around random pivots, O(n) expected.
+    Quickselect,
+    /// Partition around the median of the first, middle and last values, O(n²) worst case.
+    MedianOfThree,
+    /// Partition around the median of medians of five, O(n) worst case.
+    MedianOfMedians,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 4] = [
+        Algorithm::Sort,
+        Algorithm::Quickselect,
+        Algorithm::MedianOfThree,
+        Algorithm::MedianOfMedians,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Sort => "Sort",
+            Algorithm::Quickselect => "Quickselect",
+            Algorithm::MedianOfThree => "Median of three",
+            Algorithm::MedianOfMedians => "Median of medians",
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Algorithm> {
+        match text.trim().to_lowercase().as_str() {
+            "sort" => Some(Algorithm::Sort),
+            "quickselect" | "quick" => Some(Algorithm::Quickselect),
+            "mo3" | "median-of-three" | "medianofthree" => Some(Algorithm::MedianOfThree),
+            "mom" | "median-of-medians" | "medianofmedians" => Some(Algorithm::MedianOfMedians),
+            _ => None,
+        }
+    }
+}
+
+/// The kth smallest value of `values`, counting from 0, or `None` when there are not that many.
+///
+/// `values` is reordered so that afterwards `values[..k] <= values[k] <= values[k + 1..]`.
+pub fn select_kth<T: Ord + Clone>(values: &mut [T], k: usize, algorithm: Algorithm) -> Option<T> {
+    if k >= values.len() {
+        return None;
+    }
+    match algorithm {
+        Algorithm::Sort => values.sort(),
+        Algorithm::Quickselect => random::with_rng(|rng| {
+            select_with(values, k, |part| {
+                let index = rng.gen_range(0..part.len());
+                part[index].clone()
+            })
+        }),
+        Algorithm::MedianOfThree => select_with(values, k, median_of_three),
+        Algorithm::MedianOfMedians => select_with(values, k, median_of_medians),
+    }
+    Some(values[k].clone())
+}
+
+/// Narrow down on `k` by partitioning around the pivots `choose_pivot` picks.
+fn select_with<T: Ord + Clone>(
+    values: &mut [T],
+    k: usize,
+    mut choose_pivot: impl FnMut(&mut [T]) -> T,
+) {
+    let (mut low, mut high) = (0, values.len());
+    while high - low > 1 {
+        let pivot = choose_pivot(&mut values[low..high]);
+        let (less, greater) = partition(&mut values[low..high], &pivot);
+        if k < low + less {
+            high = low + less;
+        } else if k >= low + greater {
+            low += greater;
+        } else {
+            // values[k] is equal to the pivot and already in place
+            return;
+        }
+    }
+}
+
+/// Three-way partition: afterwards `values[..less] < pivot`, `values[less..greater] == pivot`
+/// and `values[greater..] > pivot`. Returns `(less, greater)`.
+fn partition<T: Ord>(values: &mut [T], pivot: &T) -> (usize, usize) {
+    let (mut less, mut i, mut greater) = (0, 0, values.len());
+    while i < greater {
+        match values[i].cmp(pivot) {
+            std::cmp::Ordering::Less => {
+                values.swap(less, i);
+                less += 1;
+                i += 1;
+            }
+            std::cmp::Ordering::Greater => {
+                greater -= 1;
+                values.swap(i, greater);
+            }
+            std::cmp::Ordering::Equal => i += 1,
+        }
+    }
+    (less, greater)
+}
+
+/// The median of the first, middle and last values: cheap, and fine on sorted input, but
+/// easy to defeat on purpose.
+fn median_of_three<T: Ord + Clone>(values: &mut [T]) -> T {
+    let mut candidates = [
+        &values[0],
+        &values[values.len() / 2],
+        &values[values.len() - 1],
+    ];
+    candidates.sort();
+    candidates[1].clone()
+}
+
+/// A pivot guaranteed to have at least about 30% of the values on either side: the median of
+/// the medians of groups of five, itself found by median-of-medians selection.
+fn median_of_medians<T: Ord + Clone>(values: &mut [T]) -> T {
+    if values.len() <= 5 {
+        values.sort();
+        return values[values.len() / 2].clone();
+    }
+    let groups = values.len().div_ceil(5);
+    for group in 0..groups {
+        let start = group * 5;
+        let end = (start + 5).min(values.len());
+        values[start..end].sort();
+        // Gather the medians at the front
+        values.swap(group, start + (end - start) / 2);
+    }
+    let medians = &mut values[..groups];
+    select_with(medians, groups / 2, median_of_medians);
+    medians[groups / 2].clone()
+}
+
+/// Median of a non-empty list, selecting the middle values with `algorithm`.
+pub fn median<T: Ord + Sample>(values: &[T], algorithm: Algorithm) -> Option<f64> {
+    let mut values = values.to_vec();
+    let len = values.len();
+    let upper = select_kth(&mut values, len / 2, algorithm)?.to_f64();
+    if len % 2 == 1 {
+        return Some(upper);
+    }
+    // Everything before the upper middle value is no larger, so the lower one is their maximum
+    let lower = values[..len / 2].iter().max()?.to_f64();
+    Some(lower + (upper - lower) / 2.0)
+}
+
+/// Shapes of input the benchmark runs on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Input {
+    Random,
+    Sorted,
+    /// Built against median-of-three quickselect so that every partition only takes two
+    /// values off the range, driving it into quadratic time.
+    Adversarial,
+}
+
+impl Input {
+    pub const ALL: [Input; 3] = [Input::Random, Input::Sorted, Input::Adversarial];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Input::Random => "Random",
+            Input::Sorted => "Sorted",
+            Input::Adversarial => "Adversarial",
+        }
+    }
+
+    /// `size` values of this shape.
+    pub fn generate(self, size: usize) -> Vec<i64> {
+        match self {
+            Input::Random => random::with_rng(|rng| (0..size).map(|_| rng.gen()).collect()),
+            Input::Sorted => (0..size as i64).collect(),
+            Input::Adversarial => median_of_three_killer(size),
+        }
+    }
+}
+
+/// A slot of the adversarial input whose value is only decided once a pivot needs it. Until
+/// then it compares larger than every decided value, and undecided slots compare by position.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Slot {
+    value: i64,
+    position: usize,
+}
+
+const UNDECIDED: i64 = i64::MAX;
+
+/// A permutation of `1..=size` on which median-of-three quickselect for the median takes
+/// quadratic time. It runs that selection on undecided slots and, whenever a pivot is chosen,
+/// makes the first and middle value of the range the two smallest left. The pivot is then the
+/// second smallest value, and partitioning only removes those two.
+fn median_of_three_killer(size: usize) -> Vec<i64> {
+    let mut slots: Vec<Slot> = (0..size)
+        .map(|position| Slot {
+            value: UNDECIDED,
+            position,
+        })
+        .collect();
+    let mut next = 0;
+    if size > 0 {
+        select_with(&mut slots, size / 2, |part| {
+            if part.len() >= 3 {
+                for index in [0, part.len() / 2] {
+                    if part[index].value == UNDECIDED {
+                        next += 1;
+                        part[index].value = next;
+                    }
+                }
+            }
+            median_of_three(part)
+        });
+    }
+    // Decided values are 1..=next; the undecided slots follow in the order they compared in
+    slots.sort();
+    let mut values = vec![0; size];
+    for (rank, slot) in slots.into_iter().enumerate() {
+        values[slot.position] = rank as i64 + 1;
+    }
+    values
+}
+
+/// One algorithm's run over one input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timing {
+    pub input: Input,
+    pub algorithm: Algorithm,
+    pub median: f64,
+    pub elapsed: Duration,
+}
+
+/// Time every algorithm finding the median of every input shape of `size` values, or at most
+/// `MAX_ADVERSARIAL_SIZE` for the adversarial one.
+pub fn benchmark(size: usize) -> Vec<Timing> {
+    let mut timings = Vec::new();
+    for input in Input::ALL {
+        let values = match input {
+            Input::Adversarial => input.generate(size.min(MAX_ADVERSARIAL_SIZE)),
+            _ => input.generate(size),
+        };
+        for algorithm in Algorithm::ALL {
+            let start = Instant::now();
+            let median = median(&values, algorithm).unwrap_or(f64::NAN);
+            timings.push(Timing {
+                input,
+                algorithm,
+                median,
+                elapsed: start.elapsed(),
+            });
+        }
+    }
+    timings
+}
+
+pub fn print_benchmark(console: &mut dyn Console, size: usize, timings: &[Timing]) {
+    writeln!(console, "Median of {} values, time in milliseconds", size);
+    if size > MAX_ADVERSARIAL_SIZE {
+        writeln!(
+            console,
+            "The adversarial input has {} values, as it takes quadratic time to build",
+            MAX_ADVERSARIAL_SIZE
+        );
+    }
+    write!(console, "{:<12}", "Input");
+    for algorithm in Algorithm::ALL {
+        write!(console, " {:>18}", algorithm.name());
+    }
+    writeln!(console);
+    for input in Input::ALL {
+        write!(console, "{:<12}", input.name());
+        let row: Vec<&Timing> = timings.iter().filter(|t| t.input == input).collect();
+        for timing in &row {
+            write!(console, " {:>18.3}", timing.elapsed.as_secs_f64() * 1000.0);
+        }
+        writeln!(console);
+        if row.iter().any(|timing| timing.median != row[0].median) {
+            writeln!(console, "Warning: the algorithms disagree on this input!");
+        }
+    }
+}
+
+pub fn parse_size(text: &str) -> Result<usize, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(DEFAULT_BENCHMARK_SIZE);
+    }
+    match text.parse::<usize>() {
+        Ok(size) if (1..=MAX_BENCHMARK_SIZE).contains(&size) => Ok(size),
+        _ => Err(format!(
+            "Please enter a size from 1 to {}",
+            MAX_BENCHMARK_SIZE
+        )),
+    }
+}
+
+pub fn launch_benchmark(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    writeln!(console, "Selection Benchmark");
+    loop {
+        writeln!(
+            console,
+            "Enter the input size, or press Enter for {}:",
+            DEFAULT_BENCHMARK_SIZE
+        );
+        let Some(input) = console.read_line() else {
+            return;
+        };
+        match parse_size(&input) {
+            Ok(size) => {
+                let timings = benchmark(size);
+                print_benchmark(console, size, &timings);
+                return;
+            }
+            Err(message) => writeln!(console, "{}", message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+
+    fn check_partitioned(values: &[i64], k: usize) {
+        assert!(values[..k].iter().all(|value| *value <= values[k]));
+        assert!(values[k + 1..].iter().all(|value| *value >= values[k]));
+    }
+
+    #[test]
+    fn test_select_kth_matches_sorting() {
+        random::seed(17);
+        for input in Input::ALL {
+            for size in [1, 2, 5, 6, 31, 200] {
+                let values = input.generate(size);
+                let mut sorted = values.clone();
+                sorted.sort();
+                for k in [0, size / 3, size / 2, size - 1] {
+                    for algorithm in Algorithm::ALL {
+                        let mut work = values.clone();
+                        assert_eq!(
+                            select_kth(&mut work, k, algorithm),
+                            Some(sorted[k]),
+                            "{:?} {:?} size {} k {}",
+                            input,
+                            algorithm,
+                            size,
+                            k
+                        );
+                        check_partitioned(&work, k);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_duplicates_and_out_of_range() {
+        for algorithm in Algorithm::ALL {
+            let mut values = vec![5, 1, 5, 5, 2, 5, 5, 9, 5];
+            assert_eq!(select_kth(&mut values, 4, algorithm), Some(5));
+            assert_eq!(select_kth(&mut values, 9, algorithm), None);
+            assert_eq!(select_kth::<i32>(&mut [], 0, algorithm), None);
+        }
+    }
+
+    #[test]
+    fn test_median() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(median(&[3, 1, 2], algorithm), Some(2.0));
+            assert_eq!(median(&[4, 1, 3, 2], algorithm), Some(2.5));
+            assert_eq!(
+                median(&[i32::MAX, i32::MAX - 1], algorithm),
+                Some(2147483646.5)
+            );
+            assert_eq!(median::<i32>(&[], algorithm), None);
+        }
+    }
+
+    #[test]
+    fn test_adversarial_input_is_a_permutation() {
+        let mut values = Input::Adversarial.generate(20);
+        values.sort();
+        assert_eq!(values, (1..=20).collect::<Vec<i64>>());
+        for size in [0, 1, 7, 22] {
+            let mut values = Input::Adversarial.generate(size);
+            values.sort();
+            assert_eq!(values, (1..=size as i64).collect::<Vec<i64>>());
+        }
+    }
+
+    #[test]
+    fn test_adversarial_input_defeats_median_of_three() {
+        let rounds = |values: &[i64]| {
+            let mut values = values.to_vec();
+            let (mut rounds, middle) = (0, values.len() / 2);
+            select_with(&mut values, middle, |part| {
+                rounds += 1;
+                median_of_three(part)
+            });
+            rounds
+        };
+        // Two values go per round until the middle is reached
+        assert!(rounds(&Input::Adversarial.generate(1000)) >= 250);
+        assert!(rounds(&Input::Sorted.generate(1000)) <= 2);
+        random::seed(5);
+        assert!(rounds(&Input::Random.generate(1000)) < 60);
+    }
+
+    #[test]
+    fn test_benchmark_session() {
+        assert!(parse_size("0").is_err());
+        assert_eq!(parse_size(""), Ok(DEFAULT_BENCHMARK_SIZE));
+        let mut console = ScriptedConsole::new(["x", "1001"]);
+        launch_benchmark(&mut console);
+        let output = console.output();
+        assert!(output.contains("Please enter a size"));
+        assert!(output.contains("Median of 1001 values"));
+        assert!(output.contains("Adversarial"));
+        assert!(!output.contains("disagree"));
+    }
+}
@@ -1,38 +1,273 @@
+pub mod decode;
+pub mod stream;
+
+use super::word_games;
+use crate::console::Console;
+use crate::menu::Menu;
 use crate::utils;
-use regex::Regex;
-
-fn convert_to_pig_latin(word: &str) -> String {
-    let vowels = "aiueo";
-    let first_char = match word.chars().next() {
-        Some(c) => c,
-        // empty string
-        None => return word.to_string(),
-    };
-    if vowels.contains(first_char) {
-        // 1. If a word starts with a vowel, add "hay" to the end of the word
-        format!("{}hay", word)
-    } else {
-        // 2. If a word starts with a consonant, move the consonant to the end of the word and add "ay"
-        format!("{}{}ay", &word[1..], first_char)
+
+/// Vowels, including accented Latin ones. `y` counts as a vowel anywhere but first.
+const VOWELS: &str = "aeiouàáâãäåæèéêëìíîïòóôõöøœùúûüāăąēĕėęěīĭįōŏőūŭůűų";
+
+/// Whether `c` is a letter of the Latin script, the only one `VOWELS` covers.
+fn is_latin(c: char) -> bool {
+    matches!(c,
+        'a'..='z' | 'A'..='Z' | 'ª' | 'º' | 'À'..='Ö' | 'Ø'..='ö' | 'ø'..='ɏ'
+        | '\u{1E00}'..='\u{1EFF}' | '\u{2C60}'..='\u{2C7F}' | '\u{A720}'..='\u{A7FF}'
+        | '\u{AB30}'..='\u{AB6F}' | '\u{FF21}'..='\u{FF3A}' | '\u{FF41}'..='\u{FF5A}')
+}
+
+/// Whether Pig Latin applies to `word`: every letter in it is Latin, so its vowels are known.
+/// Words in other scripts, such as Greek, Cyrillic or Chinese, are left as they are.
+pub fn is_translatable(word: &str) -> bool {
+    word.chars().filter(|c| c.is_alphabetic()).all(is_latin)
+}
+
+/// Whether `c`, found at `position` in a word, is a vowel.
+pub fn is_vowel(c: char, position: usize) -> bool {
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    VOWELS.contains(lower) || (lower == 'y' && position > 0)
+}
+
+/// Whether `c` is a titlecase letter such as `ǅ`, which is neither upper nor lowercase.
+fn is_titlecase(c: char) -> bool {
+    c.is_alphabetic() && !c.is_uppercase() && !c.is_lowercase() && !c.to_lowercase().eq([c])
+}
+
+/// How a word is capitalized, so a transformed word can be given the same look.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// Lowercase or mixed case, kept as it is.
+    AsIs,
+    /// First letter uppercase or titlecase.
+    Capitalized,
+    /// Every letter uppercase, for words of two letters or more.
+    Upper,
+}
+
+impl Case {
+    /// The case of `word`, and the word lowercased as far as that case is concerned.
+    pub fn split(word: &str) -> (Case, String) {
+        let mut chars = word.chars();
+        let Some(first) = chars.next() else {
+            return (Case::AsIs, String::new());
+        };
+        let letters = word.chars().filter(|c| c.is_alphabetic()).count();
+        if letters > 1
+            && word.chars().any(char::is_uppercase)
+            && !word.chars().any(char::is_lowercase)
+        {
+            (Case::Upper, word.to_lowercase())
+        } else if first.is_uppercase() || is_titlecase(first) {
+            (
+                Case::Capitalized,
+                first.to_lowercase().chain(chars).collect(),
+            )
+        } else {
+            (Case::AsIs, word.to_string())
+        }
+    }
+
+    /// Give `word` this case.
+    pub fn apply(self, word: &str) -> String {
+        match self {
+            Case::AsIs => word.to_string(),
+            Case::Upper => word.to_uppercase(),
+            Case::Capitalized => {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }
+        }
+    }
+}
+
+/// The Pig Latin dialects, which differ in what is appended to words starting with a vowel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// "apple" becomes "applehay"
+    Hay,
+    /// "apple" becomes "appleway"
+    Way,
+    /// "apple" becomes "appleyay"
+    Yay,
+}
+
+impl Dialect {
+    pub const ALL: [Dialect; 3] = [Dialect::Hay, Dialect::Way, Dialect::Yay];
+
+    /// What vowel-initial words get appended.
+    pub fn suffix(self) -> &'static str {
+        match self {
+            Dialect::Hay => "hay",
+            Dialect::Way => "way",
+            Dialect::Yay => "yay",
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Dialect, String> {
+        let text = text.trim().to_lowercase();
+        Dialect::ALL
+            .into_iter()
+            .find(|dialect| dialect.suffix() == text)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown Pig Latin dialect \"{}\", use hay, way or yay",
+                    text
+                )
+            })
+    }
+}
+
+/// Convert one word to Pig Latin.
+///
+/// Words starting with a vowel get "hay" appended. Otherwise the leading consonants, with the
+/// `u` of a `qu`, move to the end followed by "ay". ALL CAPS and Capitalized words keep their
+/// case; the word may contain any Latin letters and apostrophes. Words with letters of other
+/// scripts are returned unchanged.
+pub fn convert_to_pig_latin(word: &str) -> String {
+    convert_word(word, Dialect::Hay)
+}
+
+/// Convert one word to Pig Latin in the given dialect.
+pub fn convert_word(word: &str, dialect: Dialect) -> String {
+    if word.is_empty() || !is_translatable(word) {
+        return word.to_string();
     }
+    let (case, lower) = Case::split(word);
+    let lower: Vec<char> = lower.chars().collect();
+
+    let converted: String = match lower.iter().enumerate().position(|(i, &c)| is_vowel(c, i)) {
+        Some(0) => lower
+            .iter()
+            .copied()
+            .chain(dialect.suffix().chars())
+            .collect(),
+        Some(vowel) => {
+            let split = if lower[vowel] == 'u' && lower[vowel - 1] == 'q' {
+                vowel + 1
+            } else {
+                vowel
+            };
+            let (cluster, rest) = lower.split_at(split);
+            rest.iter()
+                .chain(cluster)
+                .copied()
+                .chain("ay".chars())
+                .collect()
+        }
+        // No vowel at all, so there is nothing to move
+        None => lower.iter().copied().chain("ay".chars()).collect(),
+    };
+    case.apply(&converted)
+}
+
+/// Convert every word of `sentence` to Pig Latin, leaving everything else untouched.
+pub fn translate(sentence: &str) -> String {
+    translate_with(sentence, Dialect::Hay)
 }
 
-pub fn launch() {
-    utils::clear_screen();
-    let re = Regex::new(r"[a-zA-Z]+").expect("Failed to compile regex. You dumbass, wrong regex!");
+/// Convert every word of `sentence` to Pig Latin in the given dialect.
+pub fn translate_with(sentence: &str, dialect: Dialect) -> String {
+    word_games::map_words(sentence, |word| convert_word(word, dialect))
+}
+
+pub fn menu() -> Menu<'static> {
+    Menu::new("Pig Latin")
+        .entry("Translate to Pig Latin", launch)
+        .entry("Decode Pig Latin", decode::launch)
+        .entry("Translate a file", stream::launch)
+}
+
+pub fn launch(console: &mut dyn Console) {
+    utils::clear_screen(console);
     loop {
-        println!("Enter sentence to convert to Pig Latin, or press Enter to go back");
-        let sentence = utils::read_input();
+        writeln!(
+            console,
+            "Enter sentence to convert to Pig Latin, or press Enter to go back"
+        );
+        let sentence = utils::read_input(console);
         if sentence.trim().is_empty() {
             break;
         }
 
-        let replacement = |caps: &regex::Captures| {
-            let word = caps.get(0).expect("Failed to get word").as_str();
-            convert_to_pig_latin(word)
-        };
+        writeln!(console, "Pig Latin: {}", translate(&sentence));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(translate("hello apple"), "ellohay applehay");
+        assert_eq!(translate("hi, you!"), "ihay, ouyay!");
+    }
+
+    #[test]
+    fn test_consonant_clusters_and_qu() {
+        assert_eq!(convert_to_pig_latin("string"), "ingstray");
+        assert_eq!(convert_to_pig_latin("queen"), "eenquay");
+        assert_eq!(convert_to_pig_latin("square"), "aresquay");
+        assert_eq!(convert_to_pig_latin("hmm"), "hmmay");
+        assert_eq!(convert_to_pig_latin(""), "");
+    }
+
+    #[test]
+    fn test_dialects() {
+        assert_eq!(translate_with("an apple", Dialect::Way), "anway appleway");
+        assert_eq!(translate_with("Eat plums", Dialect::Yay), "Eatyay umsplay");
+        assert_eq!(Dialect::parse(" WAY "), Ok(Dialect::Way));
+        assert!(Dialect::parse("bay").is_err());
+    }
+
+    #[test]
+    fn test_y() {
+        assert_eq!(convert_to_pig_latin("yellow"), "ellowyay");
+        assert_eq!(convert_to_pig_latin("rhythm"), "ythmrhay");
+        assert_eq!(convert_to_pig_latin("my"), "ymay");
+    }
+
+    #[test]
+    fn test_case() {
+        assert_eq!(convert_to_pig_latin("Hello"), "Ellohay");
+        assert_eq!(convert_to_pig_latin("HELLO"), "ELLOHAY");
+        assert_eq!(convert_to_pig_latin("Apple"), "Applehay");
+        assert_eq!(convert_to_pig_latin("I"), "Ihay");
+        assert_eq!(
+            translate("The Quick Brown Fox"),
+            "Ethay Ickquay Ownbray Oxfay"
+        );
+    }
+
+    #[test]
+    fn test_apostrophes_and_hyphens() {
+        assert_eq!(translate("don't"), "on'tday");
+        assert_eq!(translate("Rock’n’roll"), "Ock’n’rollray");
+        assert_eq!(translate("mother-in-law"), "othermay-inhay-awlay");
+        assert_eq!(translate("'Tis the season."), "'Istay ethay easonsay.");
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        assert_eq!(translate("über"), "überhay");
+        assert_eq!(translate("Ñandú"), "Andúñay");
+        assert_eq!(translate("straße"), "aßestray");
+        assert_eq!(translate("Ärger élan"), "Ärgerhay élanhay");
+        assert_eq!(translate("hmm, 42"), "hmmay, 42");
+    }
 
-        let result = re.replace_all(&sentence, &replacement);
-        println!("Pig Latin: {}", result);
+    #[test]
+    fn test_other_scripts_are_untouched() {
+        assert_eq!(translate("你好, 42"), "你好, 42");
+        assert_eq!(translate("ΣΟΦΙΑ and Москва"), "ΣΟΦΙΑ andhay Москва");
+        assert_eq!(translate("ǅemal"), "Emalǆay");
+        assert_eq!(
+            Case::split("ǅemal"),
+            (Case::Capitalized, "ǆemal".to_string())
+        );
     }
 }
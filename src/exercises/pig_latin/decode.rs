@@ -0,0 +1,257 @@
+use super::{convert_word, Case, Dialect};
+use crate::console::Console;
+use crate::exercises::word_games;
+use crate::utils;
+use std::collections::HashSet;
+
+/// About 900 common English words and contractions, one per line.
+const ENGLISH_WORDS: &str = include_str!("words.txt");
+
+/// Known words, used to choose between the possible decodings of a word.
+pub struct WordList {
+    words: HashSet<String>,
+}
+
+impl WordList {
+    /// Read whitespace separated words. Lookups ignore case.
+    pub fn parse(text: &str) -> Self {
+        WordList {
+            words: text.split_whitespace().map(str::to_lowercase).collect(),
+        }
+    }
+
+    /// The embedded list of common English words.
+    pub fn english() -> Self {
+        WordList::parse(ENGLISH_WORDS)
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+
+    /// Keep the candidates found in the list, or all of them when none is.
+    pub fn filter(&self, candidates: Vec<String>) -> Vec<String> {
+        if candidates.iter().any(|candidate| self.contains(candidate)) {
+            candidates
+                .into_iter()
+                .filter(|candidate| self.contains(candidate))
+                .collect()
+        } else {
+            candidates
+        }
+    }
+}
+
+/// Every word that converts to the Pig Latin `word`, in a fixed order. Pig Latin does not
+/// record where the moved consonants ended, so there is often more than one: "ahay" is "a" or
+/// "ha", "ingstray" is "string" or "trings", among others. A word that is not Pig Latin has no
+/// decoding at all.
+pub fn decode_word(word: &str) -> Vec<String> {
+    decode_word_in(word, Dialect::Hay)
+}
+
+/// Every word that converts to `word` in the given dialect.
+pub fn decode_word_in(word: &str, dialect: Dialect) -> Vec<String> {
+    let (case, lower) = Case::split(word);
+    let Some(body) = lower.strip_suffix("ay") else {
+        return Vec::new();
+    };
+    let body: Vec<char> = body.chars().collect();
+
+    // Put the last `moved` letters back in front, or drop the "h" of a vowel-initial word.
+    let moved_back = (0..body.len()).map(|moved| {
+        let split = body.len() - moved;
+        body[split..]
+            .iter()
+            .chain(&body[..split])
+            .collect::<String>()
+    });
+    let vowel_initial = lower.strip_suffix(dialect.suffix()).map(str::to_string);
+
+    let mut candidates: Vec<String> = Vec::new();
+    for candidate in moved_back.chain(vowel_initial) {
+        // Apostrophes only ever join letters
+        let joined = !candidate.starts_with(['\'', '’']) && !candidate.ends_with(['\'', '’']);
+        if joined && !candidates.contains(&candidate) && convert_word(&candidate, dialect) == lower
+        {
+            candidates.push(candidate);
+        }
+    }
+    candidates
+        .iter()
+        .map(|candidate| case.apply(candidate))
+        .collect()
+}
+
+/// A Pig Latin word with more than one possible decoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ambiguity {
+    pub word: String,
+    pub candidates: Vec<String>,
+}
+
+/// The result of decoding a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    /// The text with every word replaced by its first candidate.
+    pub text: String,
+    /// The words that could not be decoded unambiguously, in order of appearance.
+    pub ambiguities: Vec<Ambiguity>,
+}
+
+/// Decode every word of `text`, leaving words that are not Pig Latin and everything between
+/// words untouched. With a word list, candidates that are known words are preferred.
+pub fn decode(text: &str, words: Option<&WordList>) -> Decoded {
+    decode_with(text, Dialect::Hay, words)
+}
+
+/// Decode every word of `text` from the given dialect.
+pub fn decode_with(text: &str, dialect: Dialect, words: Option<&WordList>) -> Decoded {
+    let mut ambiguities = Vec::new();
+    let decoded = word_games::map_words(text, |word| {
+        let mut candidates = decode_word_in(word, dialect);
+        if let Some(words) = words {
+            candidates = words.filter(candidates);
+        }
+        let first = candidates.first().cloned();
+        if candidates.len() > 1 {
+            ambiguities.push(Ambiguity {
+                word: word.to_string(),
+                candidates,
+            });
+        }
+        first.unwrap_or_else(|| word.to_string())
+    });
+    Decoded {
+        text: decoded,
+        ambiguities,
+    }
+}
+
+/// Print the decoded text, then every ambiguous word with its candidates.
+pub fn print_decoded(console: &mut dyn Console, decoded: &Decoded) {
+    writeln!(console, "English: {}", decoded.text);
+    for ambiguity in &decoded.ambiguities {
+        writeln!(
+            console,
+            "  \"{}\" could be: {}",
+            ambiguity.word,
+            ambiguity.candidates.join(", ")
+        );
+    }
+}
+
+pub fn launch(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    let words = WordList::english();
+    loop {
+        writeln!(
+            console,
+            "Enter Pig Latin to decode, or press Enter to go back"
+        );
+        let text = utils::read_input(console);
+        if text.is_empty() {
+            break;
+        }
+
+        print_decoded(console, &decode(&text, Some(&words)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+    use crate::exercises::pig_latin::{convert_to_pig_latin, translate};
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_decode_word() {
+        assert_eq!(decode_word("ahay"), vec!["ha", "a"]);
+        assert_eq!(decode_word("Ellohay"), vec!["Hello", "Ello"]);
+        assert_eq!(decode_word("eenquay"), vec!["queen", "nquee"]);
+        assert_eq!(
+            decode_word("ingstray"),
+            vec!["ringst", "trings", "string", "gstrin", "ngstri"]
+        );
+        assert_eq!(decode_word("ELLOWYAY"), vec!["YELLOW"]);
+        assert_eq!(decode_word("hmmay"), vec!["hmm"]);
+        assert!(decode_word("hello").is_empty());
+        assert!(decode_word("ay").is_empty());
+        assert_eq!(decode_word_in("away", Dialect::Way), vec!["wa", "a"]);
+    }
+
+    #[test]
+    fn test_word_list() {
+        let words = WordList::english();
+        assert!(words.contains("String"));
+        assert_eq!(words.filter(decode_word("ingstray")), vec!["string"]);
+        assert_eq!(words.filter(decode_word("ahay")), vec!["a"]);
+        assert_eq!(
+            words.filter(decode_word("ogfray")),
+            vec!["rogf", "frog", "gfro"]
+        );
+    }
+
+    #[test]
+    fn test_decode_text() {
+        let decoded = decode("Ellohay, orldway! ahay", None);
+        assert_eq!(decoded.text, "Hello, world! ha");
+        assert_eq!(decoded.ambiguities.len(), 3);
+        assert_eq!(
+            decoded.ambiguities[2],
+            Ambiguity {
+                word: "ahay".to_string(),
+                candidates: vec!["ha".to_string(), "a".to_string()],
+            }
+        );
+
+        let words = WordList::english();
+        let decoded = decode("Ethay ickquay ownbray oxfay, on'tday ogay!", Some(&words));
+        assert_eq!(decoded.text, "The quick brown fox, don't go!");
+        assert_eq!(decoded.ambiguities.len(), 1);
+        assert_eq!(decoded.ambiguities[0].word, "oxfay");
+        assert_eq!(
+            decode("othermay-inhay-awlay", Some(&words)).text,
+            "mother-in-law"
+        );
+    }
+
+    #[test]
+    fn test_launch() {
+        let mut console = ScriptedConsole::new(["ahay ookbay", ""]);
+        launch(&mut console);
+        assert!(console.output().contains("English: a book\n"));
+        assert_eq!(console.remaining_input(), 0);
+    }
+
+    fn word() -> impl Strategy<Value = String> {
+        prop_oneof![
+            "[a-z]{1,12}",
+            "[A-Z][a-z]{0,11}",
+            "[A-Z]{2,12}",
+            "[a-zàéïñöüßæø]{1,12}",
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip_word(word in word()) {
+            let encoded = convert_to_pig_latin(&word);
+            prop_assert!(decode_word(&encoded).contains(&word));
+        }
+
+        #[test]
+        fn test_round_trip_text(words in prop::collection::vec(word(), 1..8)) {
+            let text = words.join(" ");
+            let decoded = decode(&translate(&text), None);
+            let mut ambiguous = decoded.ambiguities.iter();
+            for (original, first) in words.iter().zip(decoded.text.split(' ')) {
+                if original != first {
+                    let ambiguity = ambiguous.find(|ambiguity| ambiguity.candidates[0] == first);
+                    prop_assert!(ambiguity.is_some_and(|a| a.candidates.contains(original)));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use crate::console::Console;
+use crate::utils;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+/// Pass every line of `input` through `convert` and write it to `output`, keeping the line
+/// endings ("\n" or "\r\n") as they were. Only one line is held in memory at a time. Returns
+/// the number of lines.
+pub fn translate_lines<R, W, F>(mut input: R, mut output: W, convert: F) -> Result<usize, String>
+where
+    R: BufRead,
+    W: Write,
+    F: Fn(&str) -> String,
+{
+    let mut buffer = Vec::new();
+    let mut lines = 0;
+    loop {
+        buffer.clear();
+        let read = input
+            .read_until(b'\n', &mut buffer)
+            .map_err(|err| format!("Could not read line {}: {}", lines + 1, err))?;
+        if read == 0 {
+            break;
+        }
+        lines += 1;
+
+        let line = std::str::from_utf8(&buffer)
+            .map_err(|_| format!("Line {} is not valid UTF-8", lines))?;
+        let content = line.trim_end_matches(['\r', '\n']);
+        let mut converted = convert(content);
+        converted.push_str(&line[content.len()..]);
+        output
+            .write_all(converted.as_bytes())
+            .map_err(|err| format!("Could not write line {}: {}", lines, err))?;
+    }
+    output
+        .flush()
+        .map_err(|err| format!("Could not write output: {}", err))?;
+    Ok(lines)
+}
+
+/// Adapts a console to `io::Write`. Every write must be whole UTF-8 text, which is what
+/// `translate_lines` produces.
+struct ConsoleWriter<'a>(&'a mut dyn Console);
+
+impl Write for ConsoleWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write_str(&String::from_utf8_lossy(buf));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Translate the file at `input`, or standard input when it is "-", line by line into the file
+/// at `output`, or onto the console when there is none.
+pub fn translate_file<F>(
+    console: &mut dyn Console,
+    input: &str,
+    output: Option<&str>,
+    convert: F,
+) -> Result<usize, String>
+where
+    F: Fn(&str) -> String,
+{
+    let reader: Box<dyn BufRead> = if input == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        let file = File::open(input).map_err(|err| format!("Could not read {}: {}", input, err))?;
+        Box::new(BufReader::new(file))
+    };
+
+    let Some(output) = output else {
+        return translate_lines(reader, ConsoleWriter(console), convert);
+    };
+    if input != "-" && same_file(input, output) {
+        return Err(format!("{} would be overwritten while reading it", input));
+    }
+    let file =
+        File::create(output).map_err(|err| format!("Could not write {}: {}", output, err))?;
+    translate_lines(reader, BufWriter::new(file), convert)
+        .map_err(|message| format!("{}: {}", output, message))
+}
+
+fn same_file(first: &str, second: &str) -> bool {
+    match (fs::canonicalize(first), fs::canonicalize(second)) {
+        (Ok(first), Ok(second)) => first == second,
+        _ => false,
+    }
+}
+
+pub fn launch(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    writeln!(
+        console,
+        "Enter the file to translate (- for standard input), or press Enter to go back"
+    );
+    let input = utils::read_input(console);
+    if input.is_empty() {
+        return;
+    }
+    writeln!(
+        console,
+        "Enter the file to write to, or press Enter to show the translation"
+    );
+    let output = utils::read_input(console);
+    let output = (!output.is_empty()).then_some(output.as_str());
+
+    match translate_file(console, &input, output, super::translate) {
+        Ok(lines) => {
+            if let Some(output) = output {
+                writeln!(console, "Translated {} lines into {}", lines, output);
+            }
+        }
+        Err(message) => writeln!(console, "{}", message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+    use crate::exercises::pig_latin::translate;
+    use std::io::Cursor;
+
+    fn translate_bytes(input: &[u8]) -> Result<String, String> {
+        let mut output = Vec::new();
+        translate_lines(Cursor::new(input), &mut output, translate)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_translate_lines_keeps_layout() {
+        assert_eq!(
+            translate_bytes(b"Hello,  world!\r\n\n\ttabs\tand spaces  \nno newline").unwrap(),
+            "Ellohay,  orldway!\r\n\n\tabstay\tandhay acesspay  \nonay ewlinenay"
+        );
+        assert_eq!(translate_bytes(b"").unwrap(), "");
+        assert_eq!(
+            translate_bytes("ok\n\u{fe}\n".as_bytes()).unwrap(),
+            "okhay\n\u{fe}ay\n"
+        );
+        assert_eq!(
+            translate_bytes(b"ok\n\xff\n"),
+            Err("Line 2 is not valid UTF-8".to_string())
+        );
+    }
+
+    #[test]
+    fn test_translate_lines_counts_lines() {
+        let input = "pig latin\n".repeat(10_000);
+        let mut output = Vec::new();
+        let lines = translate_lines(Cursor::new(input.as_bytes()), &mut output, translate);
+        assert_eq!(lines, Ok(10_000));
+        assert_eq!(output, "igpay atinlay\n".repeat(10_000).into_bytes());
+    }
+
+    #[test]
+    fn test_translate_file() {
+        let dir = std::env::temp_dir().join(format!("pig_latin_stream_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input.txt");
+        let output = dir.join("output.txt");
+        fs::write(&input, "The quick\r\nbrown fox.\r\n").unwrap();
+        let (input, output) = (input.to_str().unwrap(), output.to_str().unwrap());
+
+        let mut console = ScriptedConsole::new([input, output]);
+        launch(&mut console);
+        assert_eq!(
+            fs::read_to_string(output).unwrap(),
+            "Ethay ickquay\r\nownbray oxfay.\r\n"
+        );
+        assert!(console
+            .output()
+            .ends_with(&format!("Translated 2 lines into {}\n", output)));
+
+        let mut console = ScriptedConsole::new([input, ""]);
+        launch(&mut console);
+        assert!(console
+            .output()
+            .ends_with("Ethay ickquay\r\nownbray oxfay.\r\n"));
+
+        let mut console = ScriptedConsole::default();
+        assert_eq!(
+            translate_file(&mut console, input, Some(input), translate),
+            Err(format!("{} would be overwritten while reading it", input))
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
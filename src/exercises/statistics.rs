@@ -0,0 +1,550 @@
+use crate::console::Console;
+use crate::utils;
+use num_integer::Integer;
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// A value that can be summarized. Integers are summarized exactly in `i128`, floats in
+/// `f64` scaled so that no intermediate step overflows; floats must not be NaN.
+pub trait Sample: Copy + PartialOrd + Display {
+    fn to_f64(self) -> f64;
+
+    /// The value as an exact integer, or `None` for floats.
+    fn to_i128(self) -> Option<i128>;
+}
+
+macro_rules! impl_sample {
+    (integers: $($int:ty),*; floats: $($float:ty),*) => {
+        $(impl Sample for $int {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn to_i128(self) -> Option<i128> {
+                Some(i128::from(self))
+            }
+        })*
+        $(impl Sample for $float {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn to_i128(self) -> Option<i128> {
+                None
+            }
+        })*
+    };
+}
+
+impl_sample!(integers: i32, i64, u32, u64; floats: f32, f64);
+
+/// A statistic of a list: an exact fraction for integers, whose means and interpolated
+/// ranks need not be whole, and an `f64` for floats.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    /// `numerator / denominator` in lowest terms, with a positive denominator.
+    Exact(i128, i128),
+    Float(f64),
+}
+
+impl Value {
+    fn ratio(numerator: i128, denominator: i128) -> Value {
+        let divisor = numerator.gcd(&denominator);
+        Value::Exact(numerator / divisor, denominator / divisor)
+    }
+
+    /// The nearest `f64`, which for large integers can lie outside the values summarized.
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Value::Exact(numerator, denominator) => numerator as f64 / denominator as f64,
+            Value::Float(value) => value,
+        }
+    }
+}
+
+impl Display for Value {
+    /// Rounded to four decimals, without trailing zeros.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Exact(numerator, denominator) => match numerator.checked_mul(10_000) {
+                Some(scaled) => {
+                    let mut units = scaled.div_euclid(denominator);
+                    if 2 * scaled.rem_euclid(denominator) >= denominator {
+                        units += 1;
+                    }
+                    let sign = if units < 0 { "-" } else { "" };
+                    let units = units.unsigned_abs();
+                    let text = format!("{}{}.{:04}", sign, units / 10_000, units % 10_000);
+                    write!(f, "{}", trim_decimals(&text))
+                }
+                None => write!(f, "{}", format_number(self.to_f64())),
+            },
+            Value::Float(value) => write!(f, "{}", format_number(value)),
+        }
+    }
+}
+
+/// The values as exact integers, or `None` for floats.
+fn integers<T: Sample>(values: &[T]) -> Option<Vec<i128>> {
+    values.iter().map(|value| value.to_i128()).collect()
+}
+
+fn sorted<T: Sample>(values: &[T]) -> Vec<T> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    sorted
+}
+
+pub fn min<T: Sample>(values: &[T]) -> Option<T> {
+    values
+        .iter()
+        .copied()
+        .min_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+}
+
+pub fn max<T: Sample>(values: &[T]) -> Option<T> {
+    values
+        .iter()
+        .copied()
+        .max_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+}
+
+fn range_value<T: Sample>(values: &[T]) -> Option<Value> {
+    let (min, max) = (min(values)?, max(values)?);
+    Some(match (min.to_i128(), max.to_i128()) {
+        (Some(min), Some(max)) => Value::Exact(max - min, 1),
+        _ => Value::Float(max.to_f64() - min.to_f64()),
+    })
+}
+
+pub fn range<T: Sample>(values: &[T]) -> Option<f64> {
+    range_value(values).map(Value::to_f64)
+}
+
+/// The largest magnitude among `values`, to divide them by so that no difference of two of
+/// them can overflow. Never 0.
+fn scale(values: &[f64]) -> f64 {
+    let largest = values
+        .iter()
+        .fold(0.0, |largest: f64, value| largest.max(value.abs()));
+    if largest > 0.0 && largest.is_finite() {
+        largest
+    } else {
+        1.0
+    }
+}
+
+/// Mean and sum of squared deviations from it of `values / scale`, by Welford's method.
+fn moments(values: &[f64], scale: f64) -> (f64, f64) {
+    let (mut mean, mut squares) = (0.0, 0.0);
+    for (i, value) in values.iter().enumerate() {
+        let value = value / scale;
+        let delta = value - mean;
+        mean += delta / (i + 1) as f64;
+        squares += delta * (value - mean);
+    }
+    (mean, squares)
+}
+
+fn mean_value<T: Sample>(values: &[T]) -> Option<Value> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(match integers(values) {
+        Some(integers) => Value::ratio(integers.iter().sum(), integers.len() as i128),
+        None => {
+            let floats: Vec<f64> = values.iter().map(|value| value.to_f64()).collect();
+            let scale = scale(&floats);
+            Value::Float(moments(&floats, scale).0 * scale)
+        }
+    })
+}
+
+/// Arithmetic mean: exact for integers, then rounded to the nearest `f64`.
+pub fn mean<T: Sample>(values: &[T]) -> Option<f64> {
+    mean_value(values).map(Value::to_f64)
+}
+
+/// Sum of squared deviations from the mean as `(sum, scale)`, standing for `sum * scale²`.
+/// Integer deviations are taken from the exact mean; floats are scaled down first.
+fn squared_deviations<T: Sample>(values: &[T]) -> Option<(f64, f64)> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(match integers(values) {
+        Some(integers) => {
+            let (sum, count) = (integers.iter().sum::<i128>(), integers.len() as i128);
+            let squares = integers
+                .iter()
+                .map(|value| ((value * count - sum) as f64 / count as f64).powi(2))
+                .sum();
+            (squares, 1.0)
+        }
+        None => {
+            let floats: Vec<f64> = values.iter().map(|value| value.to_f64()).collect();
+            let scale = scale(&floats);
+            (moments(&floats, scale).1, scale)
+        }
+    })
+}
+
+/// The variance with `squares` divided by `divisor`, and its square root. The variance can
+/// overflow to infinity even when the standard deviation does not.
+fn spread(squares: f64, scale: f64, divisor: usize) -> (f64, f64) {
+    let scaled = squares / divisor as f64;
+    (scaled * scale * scale, scaled.sqrt() * scale)
+}
+
+/// Variance of a whole population.
+pub fn population_variance<T: Sample>(values: &[T]) -> Option<f64> {
+    let (squares, scale) = squared_deviations(values)?;
+    Some(spread(squares, scale, values.len()).0)
+}
+
+/// Unbiased variance of a sample, which needs at least two values.
+pub fn sample_variance<T: Sample>(values: &[T]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let (squares, scale) = squared_deviations(values)?;
+    Some(spread(squares, scale, values.len() - 1).0)
+}
+
+pub fn population_std_dev<T: Sample>(values: &[T]) -> Option<f64> {
+    let (squares, scale) = squared_deviations(values)?;
+    Some(spread(squares, scale, values.len()).1)
+}
+
+pub fn sample_std_dev<T: Sample>(values: &[T]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let (squares, scale) = squared_deviations(values)?;
+    Some(spread(squares, scale, values.len() - 1).1)
+}
+
+/// The `p`th percentile, 0 to 100, interpolating linearly between the closest ranks.
+pub fn percentile<T: Sample>(values: &[T], p: f64) -> Option<f64> {
+    if values.is_empty() || !(0.0..=100.0).contains(&p) {
+        return None;
+    }
+    Some(percentile_of_sorted(&sorted(values), p).to_f64())
+}
+
+/// Exact for integers when `p` has at most two decimals, which covers the quartiles.
+fn percentile_of_sorted<T: Sample>(sorted: &[T], p: f64) -> Value {
+    let hundredths = (p * 100.0).round();
+    if let Some(integers) = integers(sorted).filter(|_| (p * 100.0 - hundredths).abs() < 1e-9) {
+        // The rank is (len - 1) * p / 100, or `position` in ten-thousandths
+        let position = (integers.len() - 1) as i128 * hundredths as i128;
+        let (index, part) = ((position / 10_000) as usize, position % 10_000);
+        let lower = integers[index];
+        let upper = integers[if part > 0 { index + 1 } else { index }];
+        return Value::ratio(lower * 10_000 + (upper - lower) * part, 10_000);
+    }
+    let rank = (sorted.len() - 1) as f64 * p / 100.0;
+    let lower = sorted[rank.floor() as usize].to_f64();
+    let upper = sorted[rank.ceil() as usize].to_f64();
+    let t = rank.fract();
+    if t == 0.0 {
+        return Value::Float(lower);
+    }
+    // Weighting both ends cannot overflow, unlike the difference upper - lower
+    Value::Float(lower * (1.0 - t) + upper * t)
+}
+
+pub fn median<T: Sample>(values: &[T]) -> Option<f64> {
+    percentile(values, 50.0)
+}
+
+fn quartile_values<T: Sample>(values: &[T]) -> Option<(Value, Value, Value)> {
+    if values.is_empty() {
+        return None;
+    }
+    let sorted = sorted(values);
+    Some((
+        percentile_of_sorted(&sorted, 25.0),
+        percentile_of_sorted(&sorted, 50.0),
+        percentile_of_sorted(&sorted, 75.0),
+    ))
+}
+
+/// First, second and third quartiles.
+pub fn quartiles<T: Sample>(values: &[T]) -> Option<(f64, f64, f64)> {
+    quartile_values(values).map(|(q1, q2, q3)| (q1.to_f64(), q2.to_f64(), q3.to_f64()))
+}
+
+/// The distance between the first and third quartile.
+fn difference(q1: Value, q3: Value) -> Value {
+    match (q1, q3) {
+        (Value::Exact(n1, d1), Value::Exact(n3, d3)) => {
+            // Both denominators divide 10 000
+            Value::ratio(n3 * (10_000 / d3) - n1 * (10_000 / d1), 10_000)
+        }
+        _ => Value::Float(q3.to_f64() - q1.to_f64()),
+    }
+}
+
+pub fn interquartile_range<T: Sample>(values: &[T]) -> Option<f64> {
+    quartile_values(values).map(|(q1, _, q3)| difference(q1, q3).to_f64())
+}
+
+/// The most frequent values, in ascending order. There is no mode when every value occurs
+/// equally often, unless there is only one distinct value.
+pub fn modes<T: Sample>(values: &[T]) -> Vec<T> {
+    let sorted = sorted(values);
+    let mut runs: Vec<(T, usize)> = Vec::new();
+    for value in sorted {
+        match runs.last_mut() {
+            Some((last, count)) if *last == value => *count += 1,
+            _ => runs.push((value, 1)),
+        }
+    }
+    let highest = runs.iter().map(|&(_, count)| count).max().unwrap_or(0);
+    if runs.len() > 1 && runs.iter().all(|&(_, count)| count == highest) {
+        return Vec::new();
+    }
+    runs.into_iter()
+        .filter(|&(_, count)| count == highest)
+        .map(|(value, _)| value)
+        .collect()
+}
+
+/// Everything the toolkit computes, for a non-empty list.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary<T> {
+    pub count: usize,
+    pub min: T,
+    pub max: T,
+    pub range: Value,
+    pub mean: Value,
+    pub median: Value,
+    pub modes: Vec<T>,
+    pub population_variance: f64,
+    pub population_std_dev: f64,
+    pub sample_variance: Option<f64>,
+    pub sample_std_dev: Option<f64>,
+    pub quartiles: (Value, Value, Value),
+    pub iqr: Value,
+}
+
+pub fn summarize<T: Sample>(values: &[T]) -> Option<Summary<T>> {
+    let quartiles = quartile_values(values)?;
+    Some(Summary {
+        count: values.len(),
+        min: min(values)?,
+        max: max(values)?,
+        range: range_value(values)?,
+        mean: mean_value(values)?,
+        median: quartiles.1,
+        modes: modes(values),
+        population_variance: population_variance(values)?,
+        population_std_dev: population_std_dev(values)?,
+        sample_variance: sample_variance(values),
+        sample_std_dev: sample_std_dev(values),
+        quartiles,
+        iqr: difference(quartiles.0, quartiles.2),
+    })
+}
+
+/// Drop trailing zeros after the decimal point, and the sign of a zero.
+fn trim_decimals(text: &str) -> String {
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        _ => text.to_string(),
+    }
+}
+
+/// Round to four decimals and drop trailing zeros.
+fn format_number(value: f64) -> String {
+    trim_decimals(&format!("{:.4}", value))
+}
+
+pub fn print_summary<T: Sample>(console: &mut dyn Console, summary: &Summary<T>) {
+    let optional = |value: Option<f64>| value.map_or("n/a".to_string(), format_number);
+    let modes = if summary.modes.is_empty() {
+        "none".to_string()
+    } else {
+        let modes: Vec<String> = summary.modes.iter().map(|mode| mode.to_string()).collect();
+        modes.join(", ")
+    };
+    let (q1, q2, q3) = summary.quartiles;
+    writeln!(console, "Count:              {}", summary.count);
+    writeln!(
+        console,
+        "Min / Max:          {} / {}",
+        summary.min, summary.max
+    );
+    writeln!(console, "Range:              {}", summary.range);
+    writeln!(console, "Mean:               {}", summary.mean);
+    writeln!(console, "Median:             {}", summary.median);
+    writeln!(console, "Mode(s):            {}", modes);
+    writeln!(
+        console,
+        "Variance:           {} (population), {} (sample)",
+        format_number(summary.population_variance),
+        optional(summary.sample_variance)
+    );
+    writeln!(
+        console,
+        "Standard deviation: {} (population), {} (sample)",
+        format_number(summary.population_std_dev),
+        optional(summary.sample_std_dev)
+    );
+    writeln!(console, "Quartiles:          {} / {} / {}", q1, q2, q3);
+    writeln!(console, "IQR:                {}", summary.iqr);
+}
+
+/// Parse numbers separated by commas and/or whitespace.
+pub fn parse_values<T: FromStr>(input: &str) -> Result<Vec<T>, String> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            item.parse::<T>()
+                .map_err(|_| format!("\"{}\" is not a valid number", item))
+        })
+        .collect()
+}
+
+/// Parse finite floats, which is what `summarize` needs of them.
+pub fn parse_floats(input: &str) -> Result<Vec<f64>, String> {
+    let values: Vec<f64> = parse_values(input)?;
+    match values.iter().find(|value| !value.is_finite()) {
+        Some(value) => Err(format!("{} is not a finite number", value)),
+        None => Ok(values),
+    }
+}
+
+/// Summarize `input` as integers when it only holds integers, and as floats otherwise.
+pub fn print_report(console: &mut dyn Console, input: &str) -> Result<(), String> {
+    if let Ok(integers) = parse_values::<i64>(input) {
+        let summary = summarize(&integers).ok_or("Please enter at least one number")?;
+        print_summary(console, &summary);
+    } else {
+        let floats = parse_floats(input)?;
+        let summary = summarize(&floats).ok_or("Please enter at least one number")?;
+        print_summary(console, &summary);
+    }
+    Ok(())
+}
+
+pub fn launch(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    writeln!(console, "Statistics Summary");
+    loop {
+        writeln!(
+            console,
+            "Enter numbers separated by commas or spaces, or press Enter to exit:"
+        );
+        let input = utils::read_input(console);
+        if input.is_empty() {
+            break;
+        }
+        if let Err(message) = print_report(console, &input) {
+            writeln!(console, "{}", message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_central_tendency() {
+        let values = [2, 4, 4, 4, 5, 5, 7, 9];
+        assert_eq!(mean(&values), Some(5.0));
+        assert_eq!(median(&values), Some(4.5));
+        assert_eq!(modes(&values), vec![4]);
+        assert_eq!(modes(&[1, 1, 2, 2, 3]), vec![1, 2]);
+        assert_eq!(modes(&[1, 2, 3]), Vec::<i32>::new());
+        assert_eq!(modes(&[7, 7]), vec![7]);
+        assert_eq!(mean::<i32>(&[]), None);
+        assert_eq!(median::<f64>(&[]), None);
+    }
+
+    #[test]
+    fn test_spread() {
+        let values = [2, 4, 4, 4, 5, 5, 7, 9];
+        assert_eq!(population_variance(&values), Some(4.0));
+        assert_eq!(population_std_dev(&values), Some(2.0));
+        assert!(close(sample_variance(&values).unwrap(), 32.0 / 7.0));
+        assert_eq!(sample_variance(&[1]), None);
+        assert_eq!(range(&values), Some(7.0));
+        assert_eq!((min(&values), max(&values)), (Some(2), Some(9)));
+    }
+
+    #[test]
+    fn test_quartiles_and_percentiles() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0];
+        assert_eq!(quartiles(&values), Some((3.0, 5.0, 7.0)));
+        assert_eq!(interquartile_range(&values), Some(4.0));
+        assert_eq!(percentile(&values, 0.0), Some(1.0));
+        assert_eq!(percentile(&values, 100.0), Some(9.0));
+        assert!(close(percentile(&values, 90.0).unwrap(), 8.2));
+        assert_eq!(percentile(&values, 101.0), None);
+    }
+
+    #[test]
+    fn test_no_overflow_near_limits() {
+        assert_eq!(median(&[i32::MAX, i32::MAX - 1]), Some(2147483646.5));
+        assert_eq!(mean(&[i32::MAX, i32::MAX]), Some(i32::MAX as f64));
+        assert_eq!(range(&[i32::MIN, i32::MAX]), Some(u32::MAX as f64));
+        assert_eq!(mean(&[f64::MAX, f64::MAX]), Some(f64::MAX));
+        assert_eq!(median(&[i64::MIN, i64::MAX]), Some(-0.5));
+    }
+
+    #[test]
+    fn test_large_integers_are_exact() {
+        let summary = summarize(&[i64::MAX - 1, i64::MAX]).unwrap();
+        assert_eq!(summary.range, Value::Exact(1, 1));
+        assert_eq!(summary.mean.to_string(), "9223372036854775806.5");
+        assert_eq!(summary.median.to_string(), "9223372036854775806.5");
+        assert_eq!(summary.iqr.to_string(), "0.5");
+        assert_eq!(summary.population_variance, 0.25);
+        let summary = summarize(&[u64::MAX, u64::MAX, 0]).unwrap();
+        assert_eq!(summary.mean.to_string(), "12297829382473034410");
+        assert_eq!(Value::ratio(-1, 3).to_string(), "-0.3333");
+        assert_eq!(Value::ratio(-1, 20_000).to_string(), "0");
+    }
+
+    #[test]
+    fn test_huge_floats_do_not_overflow() {
+        let summary = summarize(&[1e308, -1e308]).unwrap();
+        assert_eq!(summary.mean, Value::Float(0.0));
+        assert_eq!(summary.median, Value::Float(0.0));
+        assert_eq!(summary.population_std_dev, 1e308);
+        assert_eq!(summary.population_variance, f64::INFINITY);
+        assert_eq!(summary.iqr, Value::Float(1e308));
+        assert_eq!(median(&[f64::MAX, f64::MAX]), Some(f64::MAX));
+    }
+
+    #[test]
+    fn test_parse_values() {
+        assert_eq!(parse_values::<i64>("3, 1 2,,4"), Ok(vec![3, 1, 2, 4]));
+        assert!(parse_values::<i64>("1.5").is_err());
+        assert_eq!(parse_floats("1.5 -2"), Ok(vec![1.5, -2.0]));
+        assert!(parse_floats("1 NaN").is_err());
+    }
+
+    #[test]
+    fn test_report_session() {
+        let mut console = ScriptedConsole::new(["2 4 4 4 5 5 7 9", "0.5, 1.5", "a", ""]);
+        launch(&mut console);
+        let output = console.output();
+        assert!(output.contains("Min / Max:          2 / 9\n"));
+        assert!(output.contains("Mean:               5\n"));
+        assert!(output.contains("Variance:           4 (population), 4.5714 (sample)\n"));
+        assert!(output.contains("Mean:               1\n"));
+        assert!(output.contains("Mode(s):            none\n"));
+        assert!(output.contains("\"a\" is not a valid number"));
+    }
+}
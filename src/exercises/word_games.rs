@@ -0,0 +1,272 @@
+pub mod ciphers;
+pub mod language_games;
+
+use crate::console::Console;
+use crate::menu::Menu;
+use crate::utils;
+use ciphers::{Atbash, Caesar, Vigenere};
+use language_games::{Opish, PigLatin, UbbiDubbi};
+use regex::Regex;
+use std::sync::LazyLock;
+
+use super::pig_latin::Dialect;
+
+/// Matches a word: a run of letters, possibly joined by apostrophes. Hyphens are not part of a
+/// word, so hyphenated words are handled part by part.
+pub fn word_regex() -> &'static Regex {
+    static WORD: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"[\p{L}\p{M}]+(?:['’][\p{L}\p{M}]+)*")
+            .expect("Failed to compile regex. You dumbass, wrong regex!")
+    });
+    &WORD
+}
+
+/// Replace every word of `text` with `convert(word)`, leaving everything else untouched.
+pub fn map_words<F>(text: &str, mut convert: F) -> String
+where
+    F: FnMut(&str) -> String,
+{
+    word_regex()
+        .replace_all(text, |caps: &regex::Captures| convert(&caps[0]))
+        .into_owned()
+}
+
+/// A reversible way of rewriting text: a cipher or a language game.
+pub trait Transform {
+    /// Name shown to the user, such as "Caesar (shift 3)".
+    fn name(&self) -> String;
+
+    fn encode(&self, text: &str) -> String;
+
+    /// Undo `encode`. Language games that lose information make a best guess.
+    fn decode(&self, text: &str) -> String;
+}
+
+/// The transformations a pipeline can be built from, as written in a pipeline and described.
+pub const STEPS: [(&str, &str); 7] = [
+    (
+        "pig-latin[:hay|way|yay]",
+        "Pig Latin, in the dialect with the given suffix",
+    ),
+    ("ubbi-dubbi", "\"ub\" before every vowel sound"),
+    ("opish", "\"op\" after every consonant"),
+    (
+        "caesar[:<shift>]",
+        "Caesar cipher, shifting letters by 3 or <shift>",
+    ),
+    ("rot13", "Caesar cipher with a shift of 13"),
+    ("atbash", "Atbash cipher, reversing the alphabet"),
+    ("vigenere:<key>", "Vigenère cipher with a key of letters"),
+];
+
+/// Parse one step of a pipeline, such as "pig-latin:way" or "vigenere:lemon".
+pub fn parse_step(spec: &str) -> Result<Box<dyn Transform>, String> {
+    let spec = spec.trim();
+    let (name, argument) = match spec.split_once(':') {
+        Some((name, argument)) => (name.trim(), Some(argument.trim())),
+        None => (spec, None),
+    };
+    let step: Box<dyn Transform> = match (name.to_lowercase().as_str(), argument) {
+        ("pig-latin", dialect) => {
+            let dialect = dialect.map_or(Ok(Dialect::Hay), Dialect::parse)?;
+            Box::new(PigLatin::new(dialect))
+        }
+        ("ubbi-dubbi", None) => Box::new(UbbiDubbi),
+        ("opish", None) => Box::new(Opish),
+        ("caesar", shift) => {
+            let shift = match shift {
+                Some(shift) => shift
+                    .parse::<i32>()
+                    .map_err(|_| format!("\"{}\" is not a valid shift", shift))?,
+                None => 3,
+            };
+            Box::new(Caesar::new(shift))
+        }
+        ("rot13", None) => Box::new(Caesar::rot13()),
+        ("atbash", None) => Box::new(Atbash),
+        ("vigenere", Some(key)) => Box::new(Vigenere::new(key)?),
+        ("vigenere", None) => return Err("vigenere needs a key, as in vigenere:lemon".to_string()),
+        ("ubbi-dubbi" | "opish" | "rot13" | "atbash", Some(_)) => {
+            return Err(format!("{} does not take an argument", name))
+        }
+        _ => return Err(format!("Unknown transformation \"{}\"", spec)),
+    };
+    Ok(step)
+}
+
+/// Transformations applied one after the other. Decoding undoes them in reverse order.
+#[derive(Default)]
+pub struct Pipeline {
+    steps: Vec<Box<dyn Transform>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Pipeline::default()
+    }
+
+    /// Add `step` at the end of the pipeline.
+    pub fn then<T: Transform + 'static>(mut self, step: T) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// Parse steps separated by "|", such as "pig-latin:way | rot13".
+    pub fn parse(spec: &str) -> Result<Pipeline, String> {
+        let steps = spec
+            .split('|')
+            .map(parse_step)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Pipeline { steps })
+    }
+}
+
+impl Transform for Pipeline {
+    fn name(&self) -> String {
+        let names: Vec<String> = self.steps.iter().map(|step| step.name()).collect();
+        names.join(" | ")
+    }
+
+    fn encode(&self, text: &str) -> String {
+        self.steps
+            .iter()
+            .fold(text.to_string(), |text, step| step.encode(&text))
+    }
+
+    fn decode(&self, text: &str) -> String {
+        self.steps
+            .iter()
+            .rev()
+            .fold(text.to_string(), |text, step| step.decode(&text))
+    }
+}
+
+/// Whether text is encoded or decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Encode,
+    Decode,
+}
+
+/// Ask for a pipeline until a valid one is given. Returns `None` if the user goes back.
+fn choose_pipeline(console: &mut dyn Console) -> Option<Pipeline> {
+    writeln!(console, "Transformations:");
+    for (spec, description) in STEPS {
+        writeln!(console, "  {:<25} {}", spec, description);
+    }
+    loop {
+        writeln!(
+            console,
+            "Enter transformations separated by |, or press Enter to go back"
+        );
+        let spec = utils::read_input(console);
+        if spec.is_empty() {
+            return None;
+        }
+        match Pipeline::parse(&spec) {
+            Ok(pipeline) => return Some(pipeline),
+            Err(message) => writeln!(console, "{}", message),
+        }
+    }
+}
+
+pub fn launch(console: &mut dyn Console, direction: Direction) {
+    utils::clear_screen(console);
+    let Some(pipeline) = choose_pipeline(console) else {
+        return;
+    };
+    let verb = match direction {
+        Direction::Encode => "encode",
+        Direction::Decode => "decode",
+    };
+    writeln!(console, "Using {}", pipeline.name());
+    loop {
+        writeln!(console, "Enter text to {}, or press Enter to go back", verb);
+        let text = utils::read_input(console);
+        if text.is_empty() {
+            break;
+        }
+        match direction {
+            Direction::Encode => writeln!(console, "Encoded: {}", pipeline.encode(&text)),
+            Direction::Decode => writeln!(console, "Decoded: {}", pipeline.decode(&text)),
+        }
+    }
+}
+
+pub fn menu() -> Menu<'static> {
+    Menu::new("Word Games")
+        .entry("Encode text", |console| launch(console, Direction::Encode))
+        .entry("Decode text", |console| launch(console, Direction::Decode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+
+    #[test]
+    fn test_map_words() {
+        assert_eq!(
+            map_words("don't stop-me now!", |word| word.len().to_string()),
+            "5 4-2 3!"
+        );
+    }
+
+    #[test]
+    fn test_parse_step() {
+        assert_eq!(parse_step(" pig-latin ").unwrap().name(), "Pig Latin (hay)");
+        assert_eq!(
+            parse_step("Pig-Latin:way").unwrap().name(),
+            "Pig Latin (way)"
+        );
+        assert_eq!(parse_step("caesar").unwrap().name(), "Caesar (shift 3)");
+        assert_eq!(parse_step("caesar:-1").unwrap().name(), "Caesar (shift 25)");
+        assert_eq!(parse_step("rot13").unwrap().name(), "ROT13");
+        assert_eq!(
+            parse_step("vigenere:Lemon").unwrap().name(),
+            "Vigenère (key LEMON)"
+        );
+        for spec in [
+            "pig-latin:bay",
+            "caesar:x",
+            "vigenere",
+            "vigenere:k3y",
+            "atbash:1",
+            "morse",
+        ] {
+            assert!(parse_step(spec).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn test_pipeline() {
+        let pipeline = Pipeline::parse("pig-latin:way | rot13").unwrap();
+        assert_eq!(pipeline.name(), "Pig Latin (way) | ROT13");
+        assert_eq!(pipeline.encode("Hello, apple!"), "Ryybunl, nccyrjnl!");
+        assert_eq!(pipeline.decode("Ryybunl, nccyrjnl!"), "Hello, apple!");
+
+        let pipeline = Pipeline::new()
+            .then(Opish)
+            .then(Caesar::new(1))
+            .then(Atbash);
+        let encoded = pipeline.encode("Cat");
+        assert_eq!(pipeline.decode(&encoded), "Cat");
+        assert!(Pipeline::parse("rot13 | | atbash").is_err());
+    }
+
+    #[test]
+    fn test_launch() {
+        let mut console = ScriptedConsole::new(["morse", "ubbi-dubbi|rot13", "Hello", ""]);
+        launch(&mut console, Direction::Encode);
+        assert!(console
+            .output()
+            .contains("Unknown transformation \"morse\""));
+        assert!(console.output().contains("Using Ubbi Dubbi | ROT13\n"));
+        assert!(console.output().contains("Encoded: Uhoryyhob\n"));
+
+        let mut console = ScriptedConsole::new(["ubbi-dubbi|rot13", "Uhoryyhob", ""]);
+        launch(&mut console, Direction::Decode);
+        assert!(console.output().contains("Decoded: Hello\n"));
+        assert_eq!(console.remaining_input(), 0);
+    }
+}
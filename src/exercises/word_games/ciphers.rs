@@ -0,0 +1,169 @@
+use super::Transform;
+
+/// Apply `map` to the position of an ASCII letter in the alphabet, keeping its case. Every
+/// other character is left alone.
+fn map_letter(c: char, map: impl FnOnce(u8) -> u8) -> char {
+    let base = match c {
+        'a'..='z' => b'a',
+        'A'..='Z' => b'A',
+        _ => return c,
+    };
+    (base + map(c as u8 - base) % 26) as char
+}
+
+/// Shifts every letter `shift` places along the alphabet.
+pub struct Caesar {
+    shift: u8,
+}
+
+impl Caesar {
+    /// Any shift is accepted, negative ones shift backwards.
+    pub fn new(shift: i32) -> Self {
+        Caesar {
+            shift: shift.rem_euclid(26) as u8,
+        }
+    }
+
+    pub fn rot13() -> Self {
+        Caesar::new(13)
+    }
+}
+
+impl Transform for Caesar {
+    fn name(&self) -> String {
+        if self.shift == 13 {
+            "ROT13".to_string()
+        } else {
+            format!("Caesar (shift {})", self.shift)
+        }
+    }
+
+    fn encode(&self, text: &str) -> String {
+        text.chars()
+            .map(|c| map_letter(c, |letter| letter + self.shift))
+            .collect()
+    }
+
+    fn decode(&self, text: &str) -> String {
+        text.chars()
+            .map(|c| map_letter(c, |letter| letter + 26 - self.shift))
+            .collect()
+    }
+}
+
+/// Replaces every letter with the one at the same place from the end of the alphabet.
+pub struct Atbash;
+
+impl Transform for Atbash {
+    fn name(&self) -> String {
+        "Atbash".to_string()
+    }
+
+    fn encode(&self, text: &str) -> String {
+        text.chars()
+            .map(|c| map_letter(c, |letter| 25 - letter))
+            .collect()
+    }
+
+    fn decode(&self, text: &str) -> String {
+        self.encode(text)
+    }
+}
+
+/// Shifts each letter by the matching letter of a repeated key. Characters that are not
+/// letters are copied and do not use up the key.
+pub struct Vigenere {
+    key: Vec<u8>,
+}
+
+impl Vigenere {
+    /// The key must be ASCII letters; case does not matter.
+    pub fn new(key: &str) -> Result<Self, String> {
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!("\"{}\" is not a valid key, use only letters", key));
+        }
+        Ok(Vigenere {
+            key: key.bytes().map(|b| b.to_ascii_lowercase() - b'a').collect(),
+        })
+    }
+
+    fn apply(&self, text: &str, decode: bool) -> String {
+        let mut key = self.key.iter().cycle();
+        text.chars()
+            .map(|c| {
+                map_letter(c, |letter| {
+                    let shift = *key.next().expect("the key is not empty");
+                    if decode {
+                        letter + 26 - shift
+                    } else {
+                        letter + shift
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+impl Transform for Vigenere {
+    fn name(&self) -> String {
+        let key: String = self.key.iter().map(|&k| (b'A' + k) as char).collect();
+        format!("Vigenère (key {})", key)
+    }
+
+    fn encode(&self, text: &str) -> String {
+        self.apply(text, false)
+    }
+
+    fn decode(&self, text: &str) -> String {
+        self.apply(text, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_caesar() {
+        let caesar = Caesar::new(3);
+        assert_eq!(caesar.encode("Hello, World! xyz"), "Khoor, Zruog! abc");
+        assert_eq!(caesar.decode("Khoor, Zruog! abc"), "Hello, World! xyz");
+        assert_eq!(Caesar::new(-23).encode("abc"), "def");
+        assert_eq!(
+            Caesar::rot13().encode("Why did the chicken cross the road?"),
+            "Jul qvq gur puvpxra pebff gur ebnq?"
+        );
+        assert_eq!(Caesar::new(5).encode("Straße über"), "Xywfßj ügjw");
+    }
+
+    #[test]
+    fn test_atbash() {
+        assert_eq!(Atbash.encode("Hello, World!"), "Svool, Dliow!");
+        assert_eq!(Atbash.decode("Svool, Dliow!"), "Hello, World!");
+    }
+
+    #[test]
+    fn test_vigenere() {
+        let vigenere = Vigenere::new("LEMON").unwrap();
+        assert_eq!(vigenere.encode("ATTACK AT DAWN"), "LXFOPV EF RNHR");
+        assert_eq!(vigenere.decode("LXFOPV EF RNHR"), "ATTACK AT DAWN");
+        assert_eq!(
+            Vigenere::new("b").unwrap().encode("Hal, 9000!"),
+            "Ibm, 9000!"
+        );
+        assert!(Vigenere::new("").is_err());
+        assert!(Vigenere::new("é").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip(text in "\\PC{0,40}", shift in -30i32..30, key in "[a-zA-Z]{1,8}") {
+            let caesar = Caesar::new(shift);
+            prop_assert_eq!(caesar.decode(&caesar.encode(&text)), text.clone());
+            prop_assert_eq!(Atbash.decode(&Atbash.encode(&text)), text.clone());
+            let vigenere = Vigenere::new(&key).unwrap();
+            prop_assert_eq!(vigenere.decode(&vigenere.encode(&text)), text);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use super::{map_words, Transform};
+use crate::exercises::pig_latin::decode::{self, WordList};
+use crate::exercises::pig_latin::{self, is_vowel, Case, Dialect};
+
+/// Pig Latin in one of its dialects. Decoding picks known English words where the Pig Latin
+/// is ambiguous.
+pub struct PigLatin {
+    dialect: Dialect,
+    words: WordList,
+}
+
+impl PigLatin {
+    pub fn new(dialect: Dialect) -> Self {
+        PigLatin {
+            dialect,
+            words: WordList::english(),
+        }
+    }
+}
+
+impl Transform for PigLatin {
+    fn name(&self) -> String {
+        format!("Pig Latin ({})", self.dialect.suffix())
+    }
+
+    fn encode(&self, text: &str) -> String {
+        pig_latin::translate_with(text, self.dialect)
+    }
+
+    fn decode(&self, text: &str) -> String {
+        decode::decode_with(text, self.dialect, Some(&self.words)).text
+    }
+}
+
+/// Ubbi Dubbi: "ub" goes before every vowel sound, so "hello" becomes "hubellubo".
+pub struct UbbiDubbi;
+
+/// Whether `c` makes a vowel sound after `previous`: the `u` of "qu" does not.
+fn is_vowel_sound(previous: Option<char>, c: char, position: usize) -> bool {
+    is_vowel(c, position) && !(c == 'u' && previous.is_some_and(|p| p.eq_ignore_ascii_case(&'q')))
+}
+
+impl Transform for UbbiDubbi {
+    fn name(&self) -> String {
+        "Ubbi Dubbi".to_string()
+    }
+
+    fn encode(&self, text: &str) -> String {
+        map_words(text, |word| {
+            let (case, lower) = Case::split(word);
+            let mut encoded = String::new();
+            let mut previous = None;
+            let mut after_vowel = false;
+            for (i, c) in lower.chars().enumerate() {
+                let vowel = is_vowel_sound(previous, c, i);
+                if vowel && !after_vowel {
+                    encoded.push_str("ub");
+                }
+                after_vowel = vowel;
+                previous = Some(c);
+                encoded.push(c);
+            }
+            case.apply(&encoded)
+        })
+    }
+
+    fn decode(&self, text: &str) -> String {
+        map_words(text, |word| {
+            let (case, lower) = Case::split(word);
+            let chars: Vec<char> = lower.chars().collect();
+            let mut decoded: Vec<char> = Vec::new();
+            let mut i = 0;
+            while i < chars.len() {
+                let vowel_follows = chars
+                    .get(i + 2)
+                    .is_some_and(|&c| is_vowel_sound(decoded.last().copied(), c, decoded.len()));
+                if chars[i..].starts_with(&['u', 'b']) && vowel_follows {
+                    // Drop the "ub" and keep the whole group of vowels after it
+                    i += 2;
+                    while i < chars.len() && is_vowel(chars[i], decoded.len()) {
+                        decoded.push(chars[i]);
+                        i += 1;
+                    }
+                } else {
+                    decoded.push(chars[i]);
+                    i += 1;
+                }
+            }
+            case.apply(&decoded.into_iter().collect::<String>())
+        })
+    }
+}
+
+/// Opish: "op" goes after every consonant, so "cat" becomes "copatop".
+pub struct Opish;
+
+fn is_consonant(c: char, position: usize) -> bool {
+    c.is_alphabetic() && !is_vowel(c, position)
+}
+
+impl Transform for Opish {
+    fn name(&self) -> String {
+        "Opish".to_string()
+    }
+
+    fn encode(&self, text: &str) -> String {
+        map_words(text, |word| {
+            let (case, lower) = Case::split(word);
+            let mut encoded = String::new();
+            for (i, c) in lower.chars().enumerate() {
+                encoded.push(c);
+                if is_consonant(c, i) {
+                    encoded.push_str("op");
+                }
+            }
+            case.apply(&encoded)
+        })
+    }
+
+    fn decode(&self, text: &str) -> String {
+        map_words(text, |word| {
+            let (case, lower) = Case::split(word);
+            let chars: Vec<char> = lower.chars().collect();
+            let mut decoded = String::new();
+            let mut i = 0;
+            while i < chars.len() {
+                decoded.push(chars[i]);
+                if is_consonant(chars[i], i) && chars[i + 1..].starts_with(&['o', 'p']) {
+                    i += 2;
+                }
+                i += 1;
+            }
+            case.apply(&decoded)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_pig_latin() {
+        let pig_latin = PigLatin::new(Dialect::Yay);
+        assert_eq!(
+            pig_latin.encode("Eat the string!"),
+            "Eatyay ethay ingstray!"
+        );
+        assert_eq!(
+            pig_latin.decode("Eatyay ethay ingstray!"),
+            "Eat the string!"
+        );
+    }
+
+    #[test]
+    fn test_ubbi_dubbi() {
+        assert_eq!(UbbiDubbi.encode("Hello, queen!"), "Hubellubo, quubeen!");
+        assert_eq!(UbbiDubbi.encode("APPLE tuba my"), "UBAPPLUBE tubububa muby");
+        assert_eq!(UbbiDubbi.decode("Hubellubo, quubeen!"), "Hello, queen!");
+        assert_eq!(
+            UbbiDubbi.decode("UBAPPLUBE tubububa muby qububit"),
+            "APPLE tuba my qubit"
+        );
+    }
+
+    #[test]
+    fn test_opish() {
+        assert_eq!(Opish.encode("Cat, yes?"), "Copatop, yopesop?");
+        assert_eq!(Opish.encode("HOP on"), "HOPOPOP onop");
+        assert_eq!(Opish.decode("Copatop, yopesop?"), "Cat, yes?");
+        assert_eq!(Opish.decode("HOPOPOP onop"), "HOP on");
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip(text in "([a-zA-Zäöüñq']{1,10}[ ,.!-]{1,2}){0,6}") {
+            prop_assert_eq!(UbbiDubbi.decode(&UbbiDubbi.encode(&text)), text.clone());
+            prop_assert_eq!(Opish.decode(&Opish.encode(&text)), text);
+        }
+    }
+}
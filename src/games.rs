@@ -1,45 +1,20 @@
-mod christmas_lyrics;
-mod fibonacci;
-mod guessing_game;
-mod temp_converter;
+pub mod christmas_lyrics;
+pub mod fibonacci;
+pub mod guessing_game;
+pub mod sequences;
+pub mod songs;
+pub mod temp_converter;
+pub mod unit_converter;
 
-use crate::utils;
+use crate::menu::Menu;
 
-pub fn launch() {
-    loop {
-        utils::clear_screen();
-        println!("Select the program to run:");
-        println!("1. Guessing Game");
-        println!("2. Temperature Converter");
-        println!("3. Fibonacci Series");
-        println!("4. Christmas Carol Lyrics");
-        println!("0. Exit");
-
-        let selection = utils::read_input();
-        let selection = match selection.trim().parse::<u32>() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Please enter a number");
-                continue;
-            }
-        };
-
-        match selection {
-            1 => guessing_game::launch(),
-            2 => temp_converter::launch(),
-            3 => fibonacci::launch(),
-            4 => christmas_lyrics::launch(),
-            0 => break,
-            _ => {
-                println!("Invalid selection");
-                continue;
-            }
-        }
-
-        println!("Do you want to run another game? (y/n)");
-        match utils::read_input().trim() {
-            "y" => continue,
-            _ => break,
-        }
-    }
+pub fn menu() -> Menu<'static> {
+    Menu::new("Games")
+        .submenu("Guessing Game", guessing_game::menu())
+        .entry("Temperature Converter", temp_converter::launch)
+        .submenu("Fibonacci Series", fibonacci::menu())
+        .submenu("Sequence Explorer", sequences::menu())
+        .submenu("Christmas Carol Lyrics", christmas_lyrics::menu())
+        .entry("Cumulative Songs", songs::launch)
+        .entry("Unit Converter", unit_converter::launch)
 }
@@ -1,42 +1,337 @@
+use super::songs::{self, Song};
+use crate::console::Console;
+use crate::menu::Menu;
 use crate::utils;
-use convert_case::{Case, Casing};
-
-pub fn launch() {
-    utils::clear_screen();
-    println!("Christmas Carol Lyrics: Twelve Days of Christmas\n");
-    let combined = [
-        ("first", "a", "partridge in a pear tree"),
-        ("second", "two", "turtle doves"),
-        ("third", "three", "French hens"),
-        ("fourth", "four", "calling birds"),
-        ("fifth", "five", "golden rings"),
-        ("sixth", "six", "geese a-laying"),
-        ("seventh", "seven", "swans a-swimming"),
-        ("eighth", "eight", "maids a-milking"),
-        ("ninth", "nine", "ladies dancing"),
-        ("tenth", "ten", "lords a-leaping"),
-        ("eleventh", "eleven", "pipers piping"),
-        ("twelfth", "twelve", "drummers drumming"),
-    ];
-    for i in 0..combined.len() {
-        println!(
-            "On the {} day of Christmas,\nmy true love gave to me",
-            combined[i].0
+use std::fs;
+use std::path::Path;
+
+/// The Twelve Days of Christmas.
+pub fn song() -> Song {
+    songs::find("twelve-days").expect("the Twelve Days of Christmas is built in")
+}
+
+/// Lyrics of the Twelve Days of Christmas, one verse per element.
+pub fn lyrics() -> Vec<String> {
+    song().verses()
+}
+
+/// How many of one gift were given over the whole song.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GiftTotal {
+    pub gift: String,
+    /// How many arrive each day once the gift is part of the verse.
+    pub per_day: u32,
+    /// On how many days the gift is given.
+    pub days: u32,
+    pub total: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Statistics {
+    pub gifts: Vec<GiftTotal>,
+    pub total_gifts: u32,
+    pub verses: usize,
+    pub lines: usize,
+    pub words: usize,
+}
+
+/// Gift totals and text counts for the Twelve Days of Christmas.
+///
+/// The gift introduced on day n comes n at a time and is given again on every remaining day.
+pub fn statistics() -> Statistics {
+    let song = song();
+    let days = song.verse_count() as u32;
+    let gifts: Vec<GiftTotal> = (1..=days)
+        .map(|day| GiftTotal {
+            gift: song
+                .item_field(day as usize, "gift")
+                .unwrap_or_default()
+                .to_string(),
+            per_day: day,
+            days: days - day + 1,
+            total: day * (days - day + 1),
+        })
+        .collect();
+    let verses = song.verses();
+    Statistics {
+        total_gifts: gifts.iter().map(|gift| gift.total).sum(),
+        gifts,
+        verses: verses.len(),
+        lines: verses.iter().map(|verse| verse.lines().count()).sum(),
+        words: verses
+            .iter()
+            .map(|verse| verse.split_whitespace().count())
+            .sum(),
+    }
+}
+
+pub fn print_statistics(console: &mut dyn Console, statistics: &Statistics) {
+    writeln!(
+        console,
+        "{:<26} {:>7} {:>5} {:>6}",
+        "Gift", "Per day", "Days", "Total"
+    );
+    for gift in &statistics.gifts {
+        writeln!(
+            console,
+            "{:<26} {:>7} {:>5} {:>6}",
+            gift.gift, gift.per_day, gift.days, gift.total
         );
-        for j in (0..=i).rev() {
-            let lyric = if j == 0 {
-                if i == 0 {
-                    format!("{} {}.", combined[j].1, combined[j].2)
-                } else if i == combined.len() - 1 {
-                    format!("and {} {}!", combined[j].1, combined[j].2)
-                } else {
-                    format!("and {} {}.", combined[j].1, combined[j].2)
+    }
+    writeln!(console, "Total gifts: {}", statistics.total_gifts);
+    writeln!(
+        console,
+        "{} verses, {} lines, {} words",
+        statistics.verses, statistics.lines, statistics.words
+    );
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Text,
+    Markdown,
+    Html,
+}
+
+impl ExportFormat {
+    /// Look a format up by name or file extension.
+    pub fn parse(text: &str) -> Option<ExportFormat> {
+        match text.trim().trim_start_matches('.').to_lowercase().as_str() {
+            "txt" | "text" => Some(ExportFormat::Text),
+            "md" | "markdown" => Some(ExportFormat::Markdown),
+            "html" | "htm" => Some(ExportFormat::Html),
+            _ => None,
+        }
+    }
+
+    /// The format a file name's extension calls for.
+    pub fn from_path(path: &Path) -> Option<ExportFormat> {
+        ExportFormat::parse(path.extension()?.to_str()?)
+    }
+}
+
+/// The whole song as a document in `format`. Markdown and HTML mark each verse's new gift.
+pub fn render(song: &Song, format: ExportFormat) -> String {
+    let mut document = match format {
+        ExportFormat::Text => format!("{}\n", song.title()),
+        ExportFormat::Markdown => format!("# {}\n", escape_markdown(song.title())),
+        ExportFormat::Html => format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n\
+             </head>\n<body>\n<h1>{0}</h1>\n",
+            escape_html(song.title())
+        ),
+    };
+    for number in 1..=song.verse_count() {
+        let lines = song.verse_lines(number).unwrap_or_default();
+        let rendered: Vec<String> = lines
+            .iter()
+            .map(|line| match format {
+                ExportFormat::Text => line.text.clone(),
+                ExportFormat::Markdown if line.newest => {
+                    format!("**{}**", escape_markdown(&line.text))
+                }
+                ExportFormat::Markdown => escape_markdown(&line.text),
+                ExportFormat::Html if line.newest => {
+                    format!("<strong>{}</strong>", escape_html(&line.text))
                 }
-            } else {
-                format!("{} {},", combined[j].1, combined[j].2)
-            };
-            println!("{}", lyric.to_case(Case::Sentence));
+                ExportFormat::Html => escape_html(&line.text),
+            })
+            .collect();
+        match format {
+            ExportFormat::Text => document.push_str(&format!("\n{}\n", rendered.join("\n"))),
+            // A trailing backslash is a hard line break in CommonMark
+            ExportFormat::Markdown => document.push_str(&format!(
+                "\n## Verse {}\n\n{}\n",
+                number,
+                rendered.join("\\\n")
+            )),
+            ExportFormat::Html => document.push_str(&format!(
+                "<h2>Verse {}</h2>\n<p>\n{}\n</p>\n",
+                number,
+                rendered.join("<br>\n")
+            )),
         }
-        println!();
+    }
+    if format == ExportFormat::Html {
+        document.push_str("</body>\n</html>\n");
+    }
+    document
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Backslash-escape characters that Markdown would read as formatting, and a leading `-`, `+`,
+/// `=` or `1.` that would start a list or underline a heading.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    for (i, c) in text.chars().enumerate() {
+        let leading = match c {
+            '-' | '+' | '=' => i == 0,
+            '.' | ')' => i > 0 && i == digits,
+            _ => false,
+        };
+        if leading || "\\`*_[]<>#~|".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Write `song` to `path`, in `format` or else the one its extension names.
+pub fn export(song: &Song, path: &Path, format: Option<ExportFormat>) -> Result<(), String> {
+    let Some(format) = format.or_else(|| ExportFormat::from_path(path)) else {
+        return Err(format!(
+            "Cannot tell the format of {}: use a .txt, .md or .html file",
+            path.display()
+        ));
+    };
+    fs::write(path, render(song, format))
+        .map_err(|err| format!("Could not write {}: {}", path.display(), err))
+}
+
+pub fn launch(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    writeln!(
+        console,
+        "Christmas Carol Lyrics: Twelve Days of Christmas\n"
+    );
+    for verse in lyrics() {
+        writeln!(console, "{}", verse);
+    }
+}
+
+pub fn launch_statistics(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    print_statistics(console, &statistics());
+}
+
+pub fn launch_export(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    let song = song();
+    loop {
+        writeln!(
+            console,
+            "Enter a file name ending in .txt, .md or .html, or press Enter to exit:"
+        );
+        let input = utils::read_input(console);
+        if input.is_empty() {
+            break;
+        }
+        match export(&song, Path::new(&input), None) {
+            Ok(()) => {
+                writeln!(console, "Saved the lyrics to {}", input);
+                break;
+            }
+            Err(message) => writeln!(console, "{}", message),
+        }
+    }
+}
+
+pub fn menu() -> Menu<'static> {
+    Menu::new("Christmas Carol Lyrics")
+        .entry("Print the lyrics", launch)
+        .entry("Karaoke", |console| {
+            songs::karaoke::launch(console, &song())
+        })
+        .entry("Statistics", launch_statistics)
+        .entry("Export to a file", launch_export)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lyrics() {
+        let verses = lyrics();
+        assert_eq!(verses.len(), 12);
+        assert_eq!(
+            verses[0],
+            "On the first day of Christmas,\nmy true love gave to me\nA partridge in a pear tree.\n"
+        );
+        assert!(verses[11].ends_with("And a partridge in a pear tree!\n"));
+        assert!(verses[6].contains("\nSix geese a-laying,\n"));
+        assert!(verses[2].contains("\nThree French hens,\n"));
+    }
+
+    #[test]
+    fn test_statistics() {
+        let statistics = statistics();
+        assert_eq!(statistics.total_gifts, 364);
+        assert_eq!(
+            statistics.gifts[0],
+            GiftTotal {
+                gift: "partridge in a pear tree".to_string(),
+                per_day: 1,
+                days: 12,
+                total: 12
+            }
+        );
+        // Geese and swans tie for the most gifts
+        assert_eq!(statistics.gifts[5].total, 42);
+        assert_eq!(statistics.gifts[6].total, 42);
+        assert_eq!(statistics.verses, 12);
+        assert_eq!(statistics.lines, 102);
+        assert_eq!(statistics.words, 425);
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let song = song();
+        let text = render(&song, ExportFormat::Text);
+        assert!(text.starts_with("The Twelve Days of Christmas\n\nOn the first day"));
+        assert_eq!(text.matches("\n\n").count(), 12);
+
+        let markdown = render(&song, ExportFormat::Markdown);
+        assert!(markdown.contains("## Verse 2\n\nOn the second day of Christmas,\\\n"));
+        assert!(markdown.contains("**Two turtle doves,**\\\nAnd a partridge"));
+        assert_eq!(
+            escape_markdown("# Five *gold* rings_"),
+            "\\# Five \\*gold\\* rings\\_"
+        );
+        assert_eq!(escape_markdown("- a-b"), "\\- a-b");
+        assert_eq!(escape_markdown("12. drummers 1.5"), "12\\. drummers 1.5");
+        assert_eq!(escape_markdown("[x](y) `z` \\"), "\\[x\\](y) \\`z\\` \\\\");
+        let stars = Song::from_json(
+            r#"{"name": "x", "title": "*Stars*", "cumulative": ["{n}"], "items": [{"n": "- one_two"}]}"#,
+        )
+        .unwrap();
+        let markdown = render(&stars, ExportFormat::Markdown);
+        assert!(markdown.starts_with("# \\*Stars\\*\n"));
+        assert!(markdown.contains("**\\- one\\_two**"));
+
+        let html = render(&song, ExportFormat::Html);
+        assert!(html.contains("<strong>Twelve drummers drumming,</strong><br>\n"));
+        assert!(html.ends_with("</p>\n</body>\n</html>\n"));
+        assert_eq!(escape_html("<a & 'b'>"), "&lt;a &amp; &#39;b&#39;&gt;");
+    }
+
+    #[test]
+    fn test_export() {
+        let dir = std::env::temp_dir().join(format!("christmas_lyrics_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("lyrics.md");
+        export(&song(), &path, None).unwrap();
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .starts_with("# The Twelve Days of Christmas\n"));
+        assert!(export(&song(), &dir.join("lyrics.pdf"), None).is_err());
+        assert!(export(&song(), &dir.join("missing").join("lyrics.txt"), None).is_err());
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
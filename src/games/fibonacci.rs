@@ -1,38 +1,266 @@
+pub mod modular;
+
+use crate::console::Console;
+use crate::menu::Menu;
 use crate::utils;
+use num_bigint::BigUint;
+
+/// Most terms the series printer will list.
+pub const MAX_TERMS: u32 = 10_000;
+/// Largest index accepted for a single term.
+pub const MAX_NTH: u64 = 1_000_000;
+
+/// The first `n` terms of the Fibonacci series, starting from 0.
+pub fn fibonacci_terms(n: u32) -> Vec<BigUint> {
+    let mut terms = Vec::with_capacity(n as usize);
+    let mut a = BigUint::ZERO;
+    let mut b = BigUint::from(1u32);
+    for _ in 0..n {
+        let c = &a + &b;
+        terms.push(std::mem::replace(&mut a, b));
+        b = c;
+    }
+    terms
+}
+
+/// F(n), computed by fast doubling in O(log n) big-integer multiplications:
+/// F(2k) = F(k) * (2F(k+1) - F(k)) and F(2k+1) = F(k)^2 + F(k+1)^2.
+pub fn fibonacci(n: u64) -> BigUint {
+    // (a, b) = (F(k), F(k+1)), where k is the prefix of n's bits handled so far
+    let mut a = BigUint::ZERO;
+    let mut b = BigUint::from(1u32);
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        let doubled = &a * ((&b << 1) - &a);
+        let doubled_next = &a * &a + &b * &b;
+        if (n >> bit) & 1 == 1 {
+            b = &doubled + &doubled_next;
+            a = doubled_next;
+        } else {
+            a = doubled;
+            b = doubled_next;
+        }
+    }
+    a
+}
+
+/// What to show of a single term.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NthOutput {
+    Full,
+    DigitCount,
+    First(usize),
+    Last(usize),
+}
+
+/// Describe F(n) as requested by `output`.
+pub fn describe_nth(n: u64, output: NthOutput) -> Result<String, String> {
+    if n > MAX_NTH {
+        return Err(format!("Please enter an index no larger than {}", MAX_NTH));
+    }
+    let digits = fibonacci(n).to_string();
+    let description = match output {
+        NthOutput::Full => format!("F({}) = {}", n, digits),
+        NthOutput::DigitCount => format!("F({}) has {} digits", n, digits.len()),
+        NthOutput::First(count) => format!(
+            "First {} digits of F({}): {}",
+            count.min(digits.len()),
+            n,
+            &digits[..count.min(digits.len())]
+        ),
+        NthOutput::Last(count) => format!(
+            "Last {} digits of F({}): {}",
+            count.min(digits.len()),
+            n,
+            &digits[digits.len() - count.min(digits.len())..]
+        ),
+    };
+    Ok(description)
+}
+
+/// Parse the number of terms, making sure it is within `MAX_TERMS`.
+pub fn parse_terms(input: &str) -> Result<u32, String> {
+    match input.trim().parse::<u32>() {
+        Ok(num) if num > MAX_TERMS => Err(format!(
+            "Please enter a number no larger than {}",
+            MAX_TERMS
+        )),
+        Ok(num) => Ok(num),
+        Err(_) => Err("Please enter a valid number".to_string()),
+    }
+}
+
+pub fn parse_nth(input: &str) -> Result<u64, String> {
+    input
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| format!("\"{}\" is not a valid index", input.trim()))
+}
+
+pub fn print_terms(console: &mut dyn Console, n: u32) {
+    for term in fibonacci_terms(n) {
+        write!(console, "{term} ");
+    }
+    writeln!(console);
+}
 
-pub fn launch() {
-    utils::clear_screen();
-    println!("Fibonacci Series");
+pub fn launch(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    writeln!(console, "Fibonacci Series");
     loop {
-        println!("Enter the number of terms, or press Enter to exit:");
-        let n = utils::read_input();
+        writeln!(
+            console,
+            "Enter the number of terms, or press Enter to exit:"
+        );
+        let n = utils::read_input(console);
         if n.trim().is_empty() {
             break;
         }
-        let n = match n.trim().parse::<u8>() {
-            Ok(num) => {
-                if num > 99 {
-                    println!("Please enter a number less than 100");
+        match parse_terms(&n) {
+            Ok(n) => print_terms(console, n),
+            Err(message) => writeln!(console, "{}", message),
+        }
+    }
+}
+
+/// Repeatedly ask for an index and print what `output` asks for.
+fn launch_nth(
+    console: &mut dyn Console,
+    title: &str,
+    ask_count: bool,
+    output: fn(usize) -> NthOutput,
+) {
+    utils::clear_screen(console);
+    writeln!(console, "{}", title);
+    loop {
+        writeln!(console, "Enter n, or press Enter to exit:");
+        let input = utils::read_input(console);
+        if input.is_empty() {
+            break;
+        }
+        let n = match parse_nth(&input) {
+            Ok(n) => n,
+            Err(message) => {
+                writeln!(console, "{}", message);
+                continue;
+            }
+        };
+        let count = if ask_count {
+            writeln!(console, "How many digits?");
+            match utils::read_input(console).parse::<usize>() {
+                Ok(count) => count,
+                Err(_) => {
+                    writeln!(console, "Please enter a valid number");
                     continue;
                 }
-                num
-            }
-            Err(_) => {
-                println!("Please enter a valid number");
-                continue;
             }
+        } else {
+            0
         };
+        match describe_nth(n, output(count)) {
+            Ok(description) => writeln!(console, "{}", description),
+            Err(message) => writeln!(console, "{}", message),
+        }
+    }
+}
+
+pub fn menu() -> Menu<'static> {
+    Menu::new("Fibonacci")
+        .entry("Print the series", launch)
+        .entry("Nth term", |console| {
+            launch_nth(console, "Nth Fibonacci number", false, |_| NthOutput::Full)
+        })
+        .entry("Number of digits of the nth term", |console| {
+            launch_nth(console, "Digits of the nth Fibonacci number", false, |_| {
+                NthOutput::DigitCount
+            })
+        })
+        .entry("First digits of the nth term", |console| {
+            launch_nth(
+                console,
+                "First digits of the nth Fibonacci number",
+                true,
+                NthOutput::First,
+            )
+        })
+        .entry("Last digits of the nth term", |console| {
+            launch_nth(
+                console,
+                "Last digits of the nth Fibonacci number",
+                true,
+                NthOutput::Last,
+            )
+        })
+        .entry("F(n) mod m for huge n", modular::launch_mod)
+        .entry("Pisano period", modular::launch_pisano)
+        .entry("Check identities", modular::launch_identities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+
+    #[test]
+    fn test_fibonacci_terms() {
+        assert_eq!(fibonacci_terms(0), Vec::<BigUint>::new());
+        let terms: Vec<u32> = fibonacci_terms(8)
+            .iter()
+            .map(|term| u32::try_from(term).unwrap())
+            .collect();
+        assert_eq!(terms, vec![0, 1, 1, 2, 3, 5, 8, 13]);
+        assert_eq!(
+            fibonacci_terms(99).last().unwrap().to_string(),
+            "135301852344706746049"
+        );
+    }
 
-        let mut a: u128 = 0;
-        let mut b: u128 = 1;
-        let mut i: u8 = 0;
-        while i < n {
-            print!("{a} ");
-            let c = a + b;
-            a = b;
-            b = c;
-            i += 1;
+    #[test]
+    fn test_fast_doubling_matches_series() {
+        let terms = fibonacci_terms(300);
+        for (n, term) in terms.iter().enumerate() {
+            assert_eq!(&fibonacci(n as u64), term);
         }
-        println!();
+        assert_eq!(fibonacci(100).to_string(), "354224848179261915075");
+    }
+
+    #[test]
+    fn test_describe_nth() {
+        assert_eq!(
+            describe_nth(10000, NthOutput::DigitCount),
+            Ok("F(10000) has 2090 digits".to_string())
+        );
+        assert_eq!(
+            describe_nth(10000, NthOutput::First(20)),
+            Ok("First 20 digits of F(10000): 33644764876431783266".to_string())
+        );
+        assert_eq!(
+            describe_nth(10000, NthOutput::Last(20)),
+            Ok("Last 20 digits of F(10000): 66073310059947366875".to_string())
+        );
+        assert_eq!(
+            describe_nth(100000, NthOutput::DigitCount),
+            Ok("F(100000) has 20899 digits".to_string())
+        );
+        assert_eq!(
+            describe_nth(10, NthOutput::Last(5)),
+            Ok("Last 2 digits of F(10): 55".to_string())
+        );
+        assert!(describe_nth(MAX_NTH + 1, NthOutput::Full).is_err());
+    }
+
+    #[test]
+    fn test_parse_terms() {
+        assert_eq!(parse_terms(" 120 "), Ok(120));
+        assert!(parse_terms("10001").is_err());
+        assert!(parse_terms("abc").is_err());
+    }
+
+    #[test]
+    fn test_first_digits_menu() {
+        let mut console = ScriptedConsole::new(["4", "1000", "5", "", "", "0"]);
+        menu().run(&mut console);
+        assert!(console
+            .output()
+            .contains("First 5 digits of F(1000): 43466"));
     }
 }
@@ -0,0 +1,299 @@
+use super::fibonacci;
+use crate::console::Console;
+use crate::utils;
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+
+/// Largest modulus the Pisano period is searched for. The period never exceeds 6m, and
+/// finding it takes one step per term.
+pub const MAX_PISANO_MODULUS: u64 = 10_000_000;
+/// Largest index the identities are checked for, as they need the full terms.
+pub const MAX_IDENTITY_INDEX: u64 = 100_000;
+
+/// Parse a non-negative index of any length, such as a 300-digit decimal string.
+pub fn parse_big_index(text: &str) -> Result<BigUint, String> {
+    let text = text.trim();
+    if text.is_empty() || !text.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(format!("\"{}\" is not a valid index", text));
+    }
+    Ok(text.parse().expect("only digits"))
+}
+
+pub fn parse_modulus(text: &str) -> Result<u64, String> {
+    match text.trim().parse::<u64>() {
+        Ok(0) => Err("The modulus must be at least 1".to_string()),
+        Ok(modulus) => Ok(modulus),
+        Err(_) => Err(format!("\"{}\" is not a valid modulus", text.trim())),
+    }
+}
+
+/// F(n) mod m, by fast doubling with every intermediate reduced mod m. Residues are below
+/// 2^64, so each product fits in a u128 but a sum of two products may not: products are
+/// reduced before they are added.
+pub fn fibonacci_mod(n: &BigUint, modulus: u64) -> u64 {
+    let m = u128::from(modulus);
+    // (a, b) = (F(k), F(k+1)) mod m for the bits of n handled so far
+    let (mut a, mut b) = (0u128, 1 % m);
+    for bit in (0..n.bits()).rev() {
+        let doubled = a * ((2 * b + m - a) % m) % m;
+        let doubled_next = (a * a % m + b * b % m) % m;
+        if n.bit(bit) {
+            a = doubled_next;
+            b = (doubled + doubled_next) % m;
+        } else {
+            a = doubled;
+            b = doubled_next;
+        }
+    }
+    a as u64
+}
+
+/// The Pisano period π(m): the length of the cycle of F(n) mod m.
+pub fn pisano_period(modulus: u64) -> Result<u64, String> {
+    if modulus == 0 || modulus > MAX_PISANO_MODULUS {
+        return Err(format!(
+            "Please enter a modulus from 1 to {}",
+            MAX_PISANO_MODULUS
+        ));
+    }
+    let start = (0, 1 % modulus);
+    let (mut a, mut b) = start;
+    let mut period = 0;
+    loop {
+        (a, b) = (b, (a + b) % modulus);
+        period += 1;
+        if (a, b) == start {
+            return Ok(period);
+        }
+    }
+}
+
+/// The outcome of checking an identity for particular indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentityCheck {
+    pub statement: String,
+    pub holds: bool,
+}
+
+/// Cassini's identity: F(n-1)·F(n+1) − F(n)² = (−1)^n, for n ≥ 1.
+pub fn check_cassini(n: u64) -> Result<IdentityCheck, String> {
+    if n == 0 || n > MAX_IDENTITY_INDEX {
+        return Err(format!("Please enter n from 1 to {}", MAX_IDENTITY_INDEX));
+    }
+    let term = |index| BigInt::from(fibonacci(index));
+    let lhs = term(n - 1) * term(n + 1) - term(n) * term(n);
+    let rhs = if n.is_multiple_of(2) { 1 } else { -1 };
+    Ok(IdentityCheck {
+        statement: format!(
+            "F({})·F({}) − F({})² = {} and (−1)^{} = {}",
+            n - 1,
+            n + 1,
+            n,
+            lhs,
+            n,
+            rhs
+        ),
+        holds: lhs == BigInt::from(rhs),
+    })
+}
+
+/// gcd(F(m), F(n)) = F(gcd(m, n)).
+pub fn check_gcd(m: u64, n: u64) -> Result<IdentityCheck, String> {
+    if m > MAX_IDENTITY_INDEX || n > MAX_IDENTITY_INDEX {
+        return Err(format!(
+            "Please enter indices no larger than {}",
+            MAX_IDENTITY_INDEX
+        ));
+    }
+    let lhs = fibonacci(m).gcd(&fibonacci(n));
+    let index = m.gcd(&n);
+    let rhs = fibonacci(index);
+    Ok(IdentityCheck {
+        statement: format!(
+            "gcd(F({}), F({})) = {} and F(gcd({}, {})) = F({}) = {}",
+            m, n, lhs, m, n, index, rhs
+        ),
+        holds: lhs == rhs,
+    })
+}
+
+/// `"<n> <m>"` to F(n) mod m.
+fn describe_mod(input: &str) -> Result<String, String> {
+    let [n, modulus] = two_arguments(input, "Enter n and m separated by a space")?;
+    let modulus = parse_modulus(modulus)?;
+    let n = parse_big_index(n)?;
+    Ok(format!(
+        "F({}) mod {} = {}",
+        n,
+        modulus,
+        fibonacci_mod(&n, modulus)
+    ))
+}
+
+fn describe_pisano(input: &str) -> Result<String, String> {
+    let modulus = parse_modulus(input)?;
+    Ok(format!("π({}) = {}", modulus, pisano_period(modulus)?))
+}
+
+/// `"cassini <n>"` or `"gcd <m> <n>"`.
+fn describe_identity(input: &str) -> Result<String, String> {
+    let parse_index = |index: &str| {
+        index
+            .parse::<u64>()
+            .map_err(|_| format!("\"{}\" is not a valid index", index))
+    };
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let check = match words.as_slice() {
+        [name, n] if name.eq_ignore_ascii_case("cassini") => check_cassini(parse_index(n)?)?,
+        [name, m, n] if name.eq_ignore_ascii_case("gcd") => {
+            check_gcd(parse_index(m)?, parse_index(n)?)?
+        }
+        _ => return Err("Enter \"cassini <n>\" or \"gcd <m> <n>\"".to_string()),
+    };
+    let verdict = if check.holds { "holds" } else { "FAILS" };
+    Ok(format!("{}: the identity {}", check.statement, verdict))
+}
+
+fn two_arguments<'a>(input: &'a str, usage: &str) -> Result<[&'a str; 2], String> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    match words.as_slice() {
+        [first, second] => Ok([first, second]),
+        _ => Err(usage.to_string()),
+    }
+}
+
+/// Keep asking with `prompt` and print what `describe` makes of each answer.
+fn prompt_loop(
+    console: &mut dyn Console,
+    title: &str,
+    prompt: &str,
+    describe: fn(&str) -> Result<String, String>,
+) {
+    utils::clear_screen(console);
+    writeln!(console, "{}", title);
+    loop {
+        writeln!(console, "{}, or press Enter to exit:", prompt);
+        let input = utils::read_input(console);
+        if input.is_empty() {
+            break;
+        }
+        match describe(&input) {
+            Ok(description) => writeln!(console, "{}", description),
+            Err(message) => writeln!(console, "{}", message),
+        }
+    }
+}
+
+pub fn launch_mod(console: &mut dyn Console) {
+    prompt_loop(
+        console,
+        "F(n) mod m",
+        "Enter n (any number of digits) and m",
+        describe_mod,
+    );
+}
+
+pub fn launch_pisano(console: &mut dyn Console) {
+    prompt_loop(console, "Pisano period", "Enter a modulus", describe_pisano);
+}
+
+pub fn launch_identities(console: &mut dyn Console) {
+    prompt_loop(
+        console,
+        "Fibonacci identities",
+        "Enter \"cassini <n>\" or \"gcd <m> <n>\"",
+        describe_identity,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+
+    fn index(text: &str) -> BigUint {
+        parse_big_index(text).unwrap()
+    }
+
+    #[test]
+    fn test_fibonacci_mod_known_values() {
+        assert_eq!(fibonacci_mod(&index("1000"), 1_000_000_007), 517_691_607);
+        let googol = format!("1{}", "0".repeat(100));
+        assert_eq!(fibonacci_mod(&index(&googol), 1_000_000_007), 175_077_019);
+        assert_eq!(fibonacci_mod(&index(&"9".repeat(300)), 1000), 626);
+        assert_eq!(fibonacci_mod(&index("0"), 10), 0);
+        assert_eq!(fibonacci_mod(&index("12345"), 1), 0);
+        assert_eq!(fibonacci_mod(&index("10"), u64::MAX), 55);
+        assert_eq!(
+            fibonacci_mod(&index("1000"), u64::MAX),
+            6_604_850_919_967_101_945
+        );
+    }
+
+    #[test]
+    fn test_fibonacci_mod_matches_full_terms() {
+        for n in 0..200u64 {
+            for modulus in [97, u64::MAX - 1] {
+                let expected = fibonacci(n) % BigUint::from(modulus);
+                assert_eq!(
+                    BigUint::from(fibonacci_mod(&BigUint::from(n), modulus)),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_pisano_period() {
+        let periods: Vec<u64> = [1, 2, 3, 5, 10, 97, 1000]
+            .iter()
+            .map(|&m| pisano_period(m).unwrap())
+            .collect();
+        assert_eq!(periods, vec![1, 3, 8, 20, 60, 196, 1500]);
+        assert!(pisano_period(0).is_err());
+        assert!(pisano_period(MAX_PISANO_MODULUS + 1).is_err());
+    }
+
+    #[test]
+    fn test_huge_index_reduces_by_pisano_period() {
+        let n = index(&"31415926535".repeat(30));
+        let period = BigUint::from(pisano_period(1000).unwrap());
+        assert_eq!(fibonacci_mod(&n, 1000), fibonacci_mod(&(&n % period), 1000));
+    }
+
+    #[test]
+    fn test_identities() {
+        let cassini = check_cassini(10).unwrap();
+        assert!(cassini.holds);
+        assert_eq!(cassini.statement, "F(9)·F(11) − F(10)² = 1 and (−1)^10 = 1");
+        assert!(check_cassini(1001).unwrap().holds);
+        assert!(check_cassini(0).is_err());
+
+        let gcd = check_gcd(12, 18).unwrap();
+        assert!(gcd.holds);
+        assert_eq!(
+            gcd.statement,
+            "gcd(F(12), F(18)) = 8 and F(gcd(12, 18)) = F(6) = 8"
+        );
+        assert!(check_gcd(1000, 750).unwrap().holds);
+        assert!(check_gcd(0, 7).unwrap().holds);
+    }
+
+    #[test]
+    fn test_parse_big_index() {
+        assert!(parse_big_index("-5").is_err());
+        assert!(parse_big_index("1e9").is_err());
+        assert!(parse_big_index("").is_err());
+        assert!(parse_modulus("0").is_err());
+    }
+
+    #[test]
+    fn test_identity_session() {
+        let mut console = ScriptedConsole::new(["gcd 21 14", "cassini x", ""]);
+        launch_identities(&mut console);
+        assert!(console
+            .output()
+            .contains("gcd(F(21), F(14)) = 13 and F(gcd(21, 14)) = F(7) = 13: the identity holds"));
+        assert!(console.output().contains("\"x\" is not a valid index"));
+    }
+}
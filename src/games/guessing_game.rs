@@ -1,31 +1,521 @@
+pub mod computer;
+pub mod high_scores;
+
+use crate::console::Console;
+use crate::menu::Menu;
+use crate::random;
 use crate::utils;
+use high_scores::{HighScores, Score};
 use rand::Rng;
 use std::cmp::Ordering;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
-pub fn launch() {
-    utils::clear_screen();
-    println!("Welcome to guessing game!");
+/// Smallest number a custom range may start at.
+pub const MIN_BOUND: i128 = i64::MIN as i128;
+/// Largest number a custom range may end at.
+pub const MAX_BOUND: i128 = u64::MAX as i128;
 
-    let secret_number = rand::thread_rng().gen_range(1..=100);
-    loop {
-        println!("Enter your guess:");
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn config(self) -> GameConfig {
+        match self {
+            Difficulty::Easy => GameConfig {
+                min: 1,
+                max: 10,
+                max_attempts: None,
+            },
+            Difficulty::Normal => GameConfig {
+                min: 1,
+                max: 100,
+                max_attempts: None,
+            },
+            Difficulty::Hard => GameConfig {
+                min: 1,
+                max: 1000,
+                max_attempts: Some(10),
+            },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+}
+
+/// The range the secret number is drawn from, and how many guesses the player gets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameConfig {
+    min: i128,
+    max: i128,
+    max_attempts: Option<u32>,
+}
 
-        let guess = utils::read_input();
-        let guess: u32 = match guess.trim().parse() {
+impl GameConfig {
+    /// Create a config for the inclusive range `min..=max`. Both ends must lie within
+    /// `MIN_BOUND..=MAX_BOUND`, and a limit on attempts must be at least one.
+    pub fn new(min: i128, max: i128, max_attempts: Option<u32>) -> Result<Self, String> {
+        if min < MIN_BOUND || max > MAX_BOUND {
+            return Err(format!(
+                "The range must lie between {} and {}",
+                MIN_BOUND, MAX_BOUND
+            ));
+        }
+        if min > max {
+            return Err(format!(
+                "The lowest number ({}) is greater than the highest ({})",
+                min, max
+            ));
+        }
+        if max_attempts == Some(0) {
+            return Err("The number of guesses must be at least 1".to_string());
+        }
+        Ok(GameConfig {
+            min,
+            max,
+            max_attempts,
+        })
+    }
+
+    pub fn min(&self) -> i128 {
+        self.min
+    }
+
+    pub fn max(&self) -> i128 {
+        self.max
+    }
+
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    /// Number of values in the range.
+    pub fn range_size(&self) -> u128 {
+        // Cannot overflow: the bounds keep the range within 2^65 values.
+        (self.max - self.min) as u128 + 1
+    }
+
+    /// Worst-case number of guesses a binary search needs to find any number in the range.
+    pub fn optimal_guesses(&self) -> u32 {
+        u128::BITS - self.range_size().leading_zeros()
+    }
+
+    fn describe(&self) -> String {
+        match self.max_attempts {
+            Some(attempts) => format!("{} to {}, {} guesses", self.min, self.max, attempts),
+            None => format!("{} to {}, unlimited guesses", self.min, self.max),
+        }
+    }
+}
+
+/// How a round of the guessing game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Won {
+        guesses: u32,
+    },
+    Lost {
+        guesses: u32,
+    },
+    /// The input ran out before the round finished.
+    Abandoned,
+}
+
+/// Play one round against `secret_number`, which must lie within the config's range.
+pub fn play(console: &mut dyn Console, config: &GameConfig, secret_number: i128) -> Outcome {
+    writeln!(
+        console,
+        "I'm thinking of a number between {} and {}.",
+        config.min, config.max
+    );
+    let mut guesses: u32 = 0;
+    let outcome = loop {
+        if let Some(max_attempts) = config.max_attempts {
+            if guesses == max_attempts {
+                writeln!(console, "Out of guesses! The number was {}.", secret_number);
+                break Outcome::Lost { guesses };
+            }
+            writeln!(
+                console,
+                "Enter your guess ({} left):",
+                max_attempts - guesses
+            );
+        } else {
+            writeln!(console, "Enter your guess:");
+        }
+
+        let Some(guess) = console.read_line() else {
+            break Outcome::Abandoned;
+        };
+        let guess: i128 = match guess.trim().parse() {
             Ok(num) => num,
             Err(_) => {
-                println!("Please enter a valid number");
+                writeln!(console, "Please enter a valid number");
                 continue;
             }
         };
+        if guess < config.min || guess > config.max {
+            writeln!(
+                console,
+                "Please guess a number between {} and {}",
+                config.min, config.max
+            );
+            continue;
+        }
+        guesses += 1;
 
         match guess.cmp(&secret_number) {
-            Ordering::Less => println!("Too small"),
-            Ordering::Greater => println!("Too large"),
+            Ordering::Less => writeln!(console, "Too small"),
+            Ordering::Greater => writeln!(console, "Too large"),
             Ordering::Equal => {
-                println!("You win!");
-                break;
+                writeln!(console, "You win!");
+                break Outcome::Won { guesses };
             }
         };
+    };
+
+    if let Outcome::Won { guesses } | Outcome::Lost { guesses } = outcome {
+        writeln!(
+            console,
+            "You made {} guess{}. A binary search needs at most {} for this range.",
+            guesses,
+            if guesses == 1 { "" } else { "es" },
+            config.optimal_guesses()
+        );
+    }
+    outcome
+}
+
+/// Ask the player for a difficulty preset or a custom range. Returns `None` if they back out.
+fn choose_config(console: &mut dyn Console) -> Option<GameConfig> {
+    loop {
+        writeln!(console, "Select the difficulty:");
+        for (i, difficulty) in Difficulty::ALL.iter().enumerate() {
+            writeln!(
+                console,
+                "{}. {} ({})",
+                i + 1,
+                difficulty.name(),
+                difficulty.config().describe()
+            );
+        }
+        writeln!(console, "{}. Custom", Difficulty::ALL.len() + 1);
+        writeln!(console, "0. Back");
+
+        let selection = console.read_line()?;
+        match selection.trim().parse::<usize>() {
+            Ok(0) => return None,
+            Ok(num) if num <= Difficulty::ALL.len() => {
+                return Some(Difficulty::ALL[num - 1].config())
+            }
+            Ok(num) if num == Difficulty::ALL.len() + 1 => match read_custom_config(console) {
+                Ok(config) => return Some(config),
+                Err(message) => writeln!(console, "{}", message),
+            },
+            _ => writeln!(console, "Invalid selection"),
+        }
+    }
+}
+
+fn read_custom_config(console: &mut dyn Console) -> Result<GameConfig, String> {
+    let mut read_number = |prompt: &str| -> Result<i128, String> {
+        writeln!(console, "{}", prompt);
+        let input = utils::read_input(console);
+        input
+            .parse::<i128>()
+            .map_err(|_| format!("\"{}\" is not a valid number", input))
+    };
+    let min = read_number("Enter the lowest number:")?;
+    let max = read_number("Enter the highest number:")?;
+
+    writeln!(
+        console,
+        "Enter the maximum number of guesses, or press Enter for unlimited:"
+    );
+    let input = utils::read_input(console);
+    let max_attempts = if input.is_empty() {
+        None
+    } else {
+        Some(
+            input
+                .parse::<u32>()
+                .map_err(|_| format!("\"{}\" is not a valid number of guesses", input))?,
+        )
+    };
+    GameConfig::new(min, max, max_attempts)
+}
+
+pub fn launch(console: &mut dyn Console) {
+    launch_with(console, HighScores::default_path().as_deref());
+}
+
+/// Play one round, offering to record a win in the high-score table at `high_scores`.
+/// Without a path no score is kept.
+pub fn launch_with(console: &mut dyn Console, mut high_scores: Option<&Path>) {
+    utils::clear_screen(console);
+    writeln!(
+        console,
+        "Welcome to guessing game! (seed {})",
+        random::current_seed()
+    );
+    if let Some(path) = high_scores {
+        let skipped = HighScores::load(path).skipped();
+        if skipped > 0 {
+            writeln!(
+                console,
+                "Skipped {} unreadable entries in the high-score file.",
+                skipped
+            );
+            writeln!(
+                console,
+                "High scores are off for this session so {} is not overwritten",
+                path.display()
+            );
+            writeln!(console, "Press enter to continue...");
+            utils::read_input(console);
+            high_scores = None;
+        }
+    }
+
+    let Some(config) = choose_config(console) else {
+        return;
+    };
+    let secret_number = random::with_rng(|rng| rng.gen_range(config.min..=config.max));
+    let start = Instant::now();
+    if let (Outcome::Won { guesses }, Some(path)) =
+        (play(console, &config, secret_number), high_scores)
+    {
+        record_high_score(console, path, &config, guesses, start.elapsed());
+    }
+}
+
+/// Reverse mode: the user picks the number and the computer guesses it.
+pub fn launch_computer(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    writeln!(console, "Think of a number and let the computer guess it!");
+
+    let Some(config) = choose_config(console) else {
+        return;
+    };
+    let Some(mut strategy) = computer::choose_strategy(console) else {
+        return;
+    };
+    random::with_rng(|rng| computer::play(console, &config, strategy.as_mut(), rng));
+}
+
+/// Play many rounds with every strategy and compare how many guesses they need.
+pub fn launch_statistics(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    writeln!(console, "Guessing Strategy Statistics");
+
+    let Some(config) = choose_config(console) else {
+        return;
+    };
+    if config.range_size() > computer::MAX_STATISTICS_RANGE {
+        writeln!(
+            console,
+            "Statistics are limited to ranges of at most {} numbers.",
+            computer::MAX_STATISTICS_RANGE
+        );
+        return;
+    }
+    writeln!(
+        console,
+        "Enter the number of games per strategy, or press Enter for 1000:"
+    );
+    let input = utils::read_input(console);
+    let games = if input.is_empty() {
+        1000
+    } else {
+        match input.parse::<u32>() {
+            Ok(games) if games > 0 => games,
+            _ => {
+                writeln!(console, "Please enter a positive number");
+                return;
+            }
+        }
+    };
+    let statistics = random::with_rng(|rng| computer::run_statistics(&config, games, rng));
+    computer::print_statistics(console, &config, &statistics);
+}
+
+pub fn menu() -> Menu<'static> {
+    Menu::new("Guessing Game")
+        .entry("Play", launch)
+        .entry("Let the computer guess", launch_computer)
+        .entry("Compare guessing strategies", launch_statistics)
+        .entry("High scores", high_scores::launch)
+}
+
+/// Offer to put a won round on the high-score table.
+fn record_high_score(
+    console: &mut dyn Console,
+    path: &Path,
+    config: &GameConfig,
+    attempts: u32,
+    elapsed: Duration,
+) {
+    let mut high_scores = HighScores::load(path);
+    let best = high_scores
+        .best_for_range(config.min, config.max)
+        .map(|best| (best.attempts, best.elapsed));
+    if let Some((best_attempts, best_elapsed)) = best {
+        writeln!(
+            console,
+            "Best score for this range: {} guesses in {}",
+            best_attempts,
+            high_scores::format_elapsed(best_elapsed)
+        );
+    }
+
+    writeln!(
+        console,
+        "Enter your name for the high-score table, or press Enter to skip:"
+    );
+    let name = utils::read_input(console);
+    if name.is_empty() {
+        return;
+    }
+    high_scores.record(Score {
+        name,
+        min: config.min,
+        max: config.max,
+        attempts,
+        elapsed,
+    });
+    if let Err(err) = high_scores.save() {
+        writeln!(console, "Could not save the high score: {}", err);
+        return;
+    }
+    if best.is_none_or(|best| (attempts, elapsed) < best) {
+        writeln!(console, "New record for this range!");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+    use rand::SeedableRng;
+    use std::fs;
+
+    #[test]
+    fn test_config_validation() {
+        assert!(GameConfig::new(-50, 50, Some(5)).is_ok());
+        assert!(GameConfig::new(0, MAX_BOUND, None).is_ok());
+        assert!(GameConfig::new(MIN_BOUND, MAX_BOUND, None).is_ok());
+        assert!(GameConfig::new(10, 1, None).is_err());
+        assert!(GameConfig::new(1, 10, Some(0)).is_err());
+        assert!(GameConfig::new(1, MAX_BOUND + 1, None).is_err());
+    }
+
+    #[test]
+    fn test_optimal_guesses() {
+        assert_eq!(GameConfig::new(5, 5, None).unwrap().optimal_guesses(), 1);
+        assert_eq!(Difficulty::Easy.config().optimal_guesses(), 4);
+        assert_eq!(Difficulty::Normal.config().optimal_guesses(), 7);
+        assert_eq!(Difficulty::Hard.config().optimal_guesses(), 10);
+        let full = GameConfig::new(MIN_BOUND, MAX_BOUND, None).unwrap();
+        assert_eq!(full.range_size(), 3 << 63);
+        assert_eq!(full.optimal_guesses(), 65);
+    }
+
+    #[test]
+    fn test_play_win() {
+        let config = GameConfig::new(-10, 10, None).unwrap();
+        let mut console = ScriptedConsole::new(["0", "abc", "11", "-9", "-7"]);
+        assert_eq!(play(&mut console, &config, -7), Outcome::Won { guesses: 3 });
+        let output = console.output();
+        assert!(output.contains("Too large"));
+        assert!(output.contains("Too small"));
+        assert!(output.contains("Please guess a number between -10 and 10"));
+        assert!(output.contains("You made 3 guesses. A binary search needs at most 5"));
+    }
+
+    #[test]
+    fn test_play_loss() {
+        let config = GameConfig::new(1, 100, Some(2)).unwrap();
+        let mut console = ScriptedConsole::new(["50", "25", "12"]);
+        assert_eq!(
+            play(&mut console, &config, 42),
+            Outcome::Lost { guesses: 2 }
+        );
+        assert!(console
+            .output()
+            .contains("Out of guesses! The number was 42."));
+        assert_eq!(console.remaining_input(), 1);
+    }
+
+    #[test]
+    fn test_play_abandoned() {
+        let config = Difficulty::Normal.config();
+        let mut console = ScriptedConsole::new(["50"]);
+        assert_eq!(play(&mut console, &config, 42), Outcome::Abandoned);
+    }
+
+    #[test]
+    fn test_seeded_launch_is_reproducible() {
+        random::seed(2024);
+        let secret: i128 = rand::rngs::StdRng::seed_from_u64(2024).gen_range(1..=100);
+        let mut console = ScriptedConsole::new(["2".to_string(), secret.to_string()]);
+        launch_with(&mut console, None);
+        assert!(console.output().contains("(seed 2024)"));
+        assert!(console.output().contains("You made 1 guess."));
+    }
+
+    #[test]
+    fn test_custom_launch() {
+        let path = std::env::temp_dir()
+            .join(format!("launch_test_{}", std::process::id()))
+            .join("high_scores.tsv");
+        let mut console = ScriptedConsole::new(["4", "5", "5", "1", "5", "Ada"]);
+        launch_with(&mut console, Some(&path));
+        assert!(console.output().contains("You win!"));
+        assert!(console.output().contains("You made 1 guess."));
+        assert!(console.output().contains("New record for this range!"));
+
+        let mut console = ScriptedConsole::new(["4", "5", "5", "1", "5", ""]);
+        launch_with(&mut console, Some(&path));
+        assert!(console
+            .output()
+            .contains("Best score for this range: 1 guesses in"));
+        assert_eq!(HighScores::load(&path).scores().len(), 1);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_damaged_high_scores_are_kept() {
+        let path = std::env::temp_dir()
+            .join(format!("damaged_scores_test_{}", std::process::id()))
+            .join("high_scores.tsv");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let contents = "Ann\t1\t10\t3\t4200\ngarbage\n";
+        fs::write(&path, contents).unwrap();
+
+        let mut console = ScriptedConsole::new(["", "4", "5", "5", "1", "5", "Ada"]);
+        launch_with(&mut console, Some(&path));
+        assert!(console
+            .output()
+            .contains("Skipped 1 unreadable entries in the high-score file."));
+        assert!(console
+            .output()
+            .contains("High scores are off for this session"));
+        assert!(console.output().contains("You win!"));
+        assert!(!console.output().contains("Enter your name"));
+        assert_eq!(console.remaining_input(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
     }
 }
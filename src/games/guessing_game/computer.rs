@@ -0,0 +1,463 @@
+use super::GameConfig;
+use crate::console::Console;
+use rand::{Rng, RngCore};
+use std::cmp::Ordering;
+
+/// Largest range the statistics run accepts, so the linear scan finishes in reasonable time.
+pub const MAX_STATISTICS_RANGE: u128 = 10_000;
+
+/// A way for the computer to pick its next guess.
+pub trait Strategy {
+    fn name(&self) -> &'static str;
+
+    /// Pick a guess within `low..=high`, the numbers still consistent with the answers so far.
+    fn next_guess(&mut self, low: i128, high: i128, rng: &mut dyn RngCore) -> i128;
+
+    /// Forget everything learned, before a new game.
+    fn reset(&mut self) {}
+
+    /// Learn how `guess` compared to the secret.
+    fn observe(&mut self, _guess: i128, _answer: Ordering) {}
+}
+
+/// Always guess the middle of the remaining range.
+pub struct BinarySearch;
+
+impl Strategy for BinarySearch {
+    fn name(&self) -> &'static str {
+        "Binary search"
+    }
+
+    fn next_guess(&mut self, low: i128, high: i128, _rng: &mut dyn RngCore) -> i128 {
+        low + (high - low) / 2
+    }
+}
+
+/// Guess any number of the remaining range at random.
+pub struct RandomGuess;
+
+impl Strategy for RandomGuess {
+    fn name(&self) -> &'static str {
+        "Random"
+    }
+
+    fn next_guess(&mut self, low: i128, high: i128, rng: &mut dyn RngCore) -> i128 {
+        rng.gen_range(low..=high)
+    }
+}
+
+/// Regula falsi with only the direction of each answer to go on. Each end of the range has a
+/// weight standing in for how far the secret is from it, and the probe is interpolated between
+/// the ends by those weights. The end a new answer moves gets weight 1, while the end that
+/// stays has its weight halved, as in the Illinois method: a secret that keeps turning out
+/// higher pulls the probes further up each time, like an exponential search.
+pub struct Interpolation {
+    low_weight: f64,
+    high_weight: f64,
+}
+
+impl Default for Interpolation {
+    fn default() -> Self {
+        Interpolation {
+            low_weight: 1.0,
+            high_weight: 1.0,
+        }
+    }
+}
+
+impl Strategy for Interpolation {
+    fn name(&self) -> &'static str {
+        "Interpolation"
+    }
+
+    fn next_guess(&mut self, low: i128, high: i128, _rng: &mut dyn RngCore) -> i128 {
+        let fraction = self.low_weight / (self.low_weight + self.high_weight);
+        let offset = ((high - low) as f64 * fraction).round() as i128;
+        (low + offset).clamp(low, high)
+    }
+
+    fn reset(&mut self) {
+        *self = Interpolation::default();
+    }
+
+    fn observe(&mut self, _guess: i128, answer: Ordering) {
+        match answer {
+            Ordering::Less => {
+                self.low_weight = 1.0;
+                self.high_weight /= 2.0;
+            }
+            Ordering::Greater => {
+                self.high_weight = 1.0;
+                self.low_weight /= 2.0;
+            }
+            Ordering::Equal => {}
+        }
+        // Keep the weights from underflowing on very long games
+        let smallest = self.low_weight.min(self.high_weight);
+        if smallest < 1e-6 {
+            self.low_weight = self.low_weight.max(1e-6);
+            self.high_weight = self.high_weight.max(1e-6);
+        }
+    }
+}
+
+/// Probe a fixed fraction of the way into the remaining range, ignoring where earlier guesses
+/// landed. The default fraction is the golden section (about 38%), which never beats the
+/// plain midpoint.
+pub struct GoldenSection {
+    fraction: f64,
+}
+
+impl Default for GoldenSection {
+    fn default() -> Self {
+        GoldenSection {
+            fraction: (3.0 - 5f64.sqrt()) / 2.0,
+        }
+    }
+}
+
+impl Strategy for GoldenSection {
+    fn name(&self) -> &'static str {
+        "Golden section"
+    }
+
+    fn next_guess(&mut self, low: i128, high: i128, _rng: &mut dyn RngCore) -> i128 {
+        let offset = ((high - low) as f64 * self.fraction).round() as i128;
+        (low + offset).clamp(low, high)
+    }
+}
+
+/// Deliberately bad: count up from the bottom of the range.
+pub struct LinearScan;
+
+impl Strategy for LinearScan {
+    fn name(&self) -> &'static str {
+        "Linear scan"
+    }
+
+    fn next_guess(&mut self, low: i128, _high: i128, _rng: &mut dyn RngCore) -> i128 {
+        low
+    }
+}
+
+/// Every strategy the computer can play with.
+pub fn strategies() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(BinarySearch),
+        Box::new(RandomGuess),
+        Box::new(Interpolation::default()),
+        Box::new(GoldenSection::default()),
+        Box::new(LinearScan),
+    ]
+}
+
+/// The numbers still consistent with every answer given so far.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    low: i128,
+    high: i128,
+}
+
+impl Bounds {
+    pub fn new(config: &GameConfig) -> Self {
+        Bounds {
+            low: config.min(),
+            high: config.max(),
+        }
+    }
+
+    pub fn low(&self) -> i128 {
+        self.low
+    }
+
+    pub fn high(&self) -> i128 {
+        self.high
+    }
+
+    /// Narrow the bounds with the answer for `guess`, where `answer` says how the guess
+    /// compares to the secret. Fails without changing anything if no number would be left.
+    pub fn narrow(&mut self, guess: i128, answer: Ordering) -> Result<(), String> {
+        let (low, high) = match answer {
+            Ordering::Less => (self.low.max(guess + 1), self.high),
+            Ordering::Greater => (self.low, self.high.min(guess - 1)),
+            Ordering::Equal => (guess, guess),
+        };
+        if low > high || guess < self.low || guess > self.high {
+            return Err(format!(
+                "That can't be right: your earlier answers mean the number is between {} and {}.",
+                self.low, self.high
+            ));
+        }
+        self.low = low;
+        self.high = high;
+        Ok(())
+    }
+}
+
+/// Let `strategy` find `secret` with perfect answers. Returns the number of guesses taken.
+pub fn simulate(
+    strategy: &mut dyn Strategy,
+    config: &GameConfig,
+    secret: i128,
+    rng: &mut dyn RngCore,
+) -> u32 {
+    let mut bounds = Bounds::new(config);
+    let mut guesses = 0;
+    strategy.reset();
+    loop {
+        let guess = strategy.next_guess(bounds.low, bounds.high, rng);
+        guesses += 1;
+        let answer = guess.cmp(&secret);
+        if answer == Ordering::Equal {
+            return guesses;
+        }
+        bounds
+            .narrow(guess, answer)
+            .expect("truthful answers are always consistent");
+        strategy.observe(guess, answer);
+    }
+}
+
+/// Average and worst-case guess counts of one strategy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statistics {
+    pub strategy: &'static str,
+    pub games: u32,
+    pub average: f64,
+    pub worst: u32,
+}
+
+/// Play `games` rounds with each strategy against the same random secrets.
+pub fn run_statistics(config: &GameConfig, games: u32, rng: &mut dyn RngCore) -> Vec<Statistics> {
+    let secrets: Vec<i128> = (0..games)
+        .map(|_| rng.gen_range(config.min()..=config.max()))
+        .collect();
+    strategies()
+        .into_iter()
+        .map(|mut strategy| {
+            let mut total: u64 = 0;
+            let mut worst = 0;
+            for &secret in &secrets {
+                let guesses = simulate(strategy.as_mut(), config, secret, rng);
+                total += guesses as u64;
+                worst = worst.max(guesses);
+            }
+            Statistics {
+                strategy: strategy.name(),
+                games,
+                average: total as f64 / games.max(1) as f64,
+                worst,
+            }
+        })
+        .collect()
+}
+
+/// How a round with the computer guessing ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComputerOutcome {
+    Found { guesses: u32 },
+    OutOfGuesses,
+    Inconsistent,
+    Abandoned,
+}
+
+/// The computer guesses the number the user is thinking of, asking after each guess.
+pub fn play(
+    console: &mut dyn Console,
+    config: &GameConfig,
+    strategy: &mut dyn Strategy,
+    rng: &mut dyn RngCore,
+) -> ComputerOutcome {
+    writeln!(
+        console,
+        "Think of a number between {} and {}. I'll guess it using {}.",
+        config.min(),
+        config.max(),
+        strategy.name().to_lowercase()
+    );
+    let mut bounds = Bounds::new(config);
+    let mut guesses = 0;
+    strategy.reset();
+    loop {
+        if config.max_attempts().is_some_and(|max| guesses == max) {
+            writeln!(console, "I'm out of guesses. You win!");
+            return ComputerOutcome::OutOfGuesses;
+        }
+        let guess = strategy.next_guess(bounds.low, bounds.high, rng);
+        guesses += 1;
+        let answer = loop {
+            writeln!(
+                console,
+                "My guess is {}. Is it too (s)mall, too (l)arge or (c)orrect?",
+                guess
+            );
+            let Some(answer) = console.read_line() else {
+                return ComputerOutcome::Abandoned;
+            };
+            match answer.trim().to_lowercase().as_str() {
+                "s" | "small" => break Ordering::Less,
+                "l" | "large" => break Ordering::Greater,
+                "c" | "correct" => break Ordering::Equal,
+                _ => writeln!(console, "Please answer s, l or c"),
+            }
+        };
+        if let Err(message) = bounds.narrow(guess, answer) {
+            writeln!(console, "{}", message);
+            return ComputerOutcome::Inconsistent;
+        }
+        strategy.observe(guess, answer);
+        if answer == Ordering::Equal {
+            writeln!(
+                console,
+                "Got it in {} guess{}!",
+                guesses,
+                if guesses == 1 { "" } else { "es" }
+            );
+            return ComputerOutcome::Found { guesses };
+        }
+    }
+}
+
+/// Ask which strategy the computer should use. Returns `None` if the user backs out.
+pub fn choose_strategy(console: &mut dyn Console) -> Option<Box<dyn Strategy>> {
+    let mut strategies = strategies();
+    loop {
+        writeln!(console, "Select the computer's strategy:");
+        for (i, strategy) in strategies.iter().enumerate() {
+            writeln!(console, "{}. {}", i + 1, strategy.name());
+        }
+        writeln!(console, "0. Back");
+        let selection = console.read_line()?;
+        match selection.trim().parse::<usize>() {
+            Ok(0) => return None,
+            Ok(num) if num <= strategies.len() => return Some(strategies.swap_remove(num - 1)),
+            _ => writeln!(console, "Invalid selection"),
+        }
+    }
+}
+
+pub fn print_statistics(console: &mut dyn Console, config: &GameConfig, statistics: &[Statistics]) {
+    writeln!(
+        console,
+        "Range {} to {} (binary search bound: {} guesses)",
+        config.min(),
+        config.max(),
+        config.optimal_guesses()
+    );
+    writeln!(
+        console,
+        "{:<15} {:>8} {:>10} {:>8}",
+        "Strategy", "Games", "Average", "Worst"
+    );
+    for stats in statistics {
+        writeln!(
+            console,
+            "{:<15} {:>8} {:>10.2} {:>8}",
+            stats.strategy, stats.games, stats.average, stats.worst
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn config(min: i128, max: i128) -> GameConfig {
+        GameConfig::new(min, max, None).unwrap()
+    }
+
+    #[test]
+    fn test_bounds_narrow() {
+        let mut bounds = Bounds::new(&config(1, 10));
+        assert!(bounds.narrow(5, Ordering::Less).is_ok());
+        assert_eq!((bounds.low(), bounds.high()), (6, 10));
+        assert!(bounds.narrow(8, Ordering::Greater).is_ok());
+        assert_eq!((bounds.low(), bounds.high()), (6, 7));
+        assert!(bounds.narrow(7, Ordering::Less).is_err());
+        assert_eq!((bounds.low(), bounds.high()), (6, 7));
+        assert!(bounds.narrow(9, Ordering::Equal).is_err());
+    }
+
+    #[test]
+    fn test_strategies_find_every_secret() {
+        let config = config(-20, 20);
+        let mut rng = StdRng::seed_from_u64(7);
+        for mut strategy in strategies() {
+            for secret in -20..=20 {
+                let guesses = simulate(strategy.as_mut(), &config, secret, &mut rng);
+                assert!((1..=41).contains(&guesses), "{}", strategy.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_binary_search_meets_bound() {
+        let config = config(1, 1000);
+        let mut rng = StdRng::seed_from_u64(1);
+        let worst = (1..=1000)
+            .map(|secret| simulate(&mut BinarySearch, &config, secret, &mut rng))
+            .max();
+        assert_eq!(worst, Some(config.optimal_guesses()));
+        assert_eq!(simulate(&mut LinearScan, &config, 1000, &mut rng), 1000);
+    }
+
+    #[test]
+    fn test_interpolation_follows_the_answers() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut strategy = Interpolation::default();
+        assert_eq!(strategy.next_guess(1, 100, &mut rng), 51);
+        // Without history the remaining range is split in the middle
+        assert_eq!(strategy.next_guess(52, 100, &mut rng), 76);
+
+        // After "too small" the probe leans towards the end that did not move
+        strategy.observe(51, Ordering::Less);
+        assert_eq!(strategy.next_guess(52, 100, &mut rng), 84);
+        strategy.observe(84, Ordering::Less);
+        assert_eq!(strategy.next_guess(85, 100, &mut rng), 97);
+        strategy.observe(97, Ordering::Greater);
+        assert_eq!(strategy.next_guess(85, 96, &mut rng), 89);
+
+        strategy.reset();
+        assert_eq!(strategy.next_guess(52, 100, &mut rng), 76);
+    }
+
+    #[test]
+    fn test_run_statistics() {
+        let config = config(1, 100);
+        let mut rng = StdRng::seed_from_u64(3);
+        let statistics = run_statistics(&config, 500, &mut rng);
+        assert_eq!(statistics.len(), 5);
+        let binary = &statistics[0];
+        assert_eq!(binary.strategy, "Binary search");
+        assert!(binary.worst <= 7);
+        let linear = &statistics[4];
+        assert!(linear.average > binary.average);
+    }
+
+    #[test]
+    fn test_play_with_user() {
+        let config = config(1, 100);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut console = ScriptedConsole::new(["s", "?", "l", "c"]);
+        let outcome = play(&mut console, &config, &mut BinarySearch, &mut rng);
+        assert_eq!(outcome, ComputerOutcome::Found { guesses: 3 });
+        let output = console.output();
+        assert!(output.contains("My guess is 50."));
+        assert!(output.contains("My guess is 75."));
+        assert!(output.contains("Please answer s, l or c"));
+        assert!(output.contains("My guess is 62."));
+    }
+
+    #[test]
+    fn test_play_detects_inconsistent_answers() {
+        let config = config(1, 3);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut console = ScriptedConsole::new(["s", "l"]);
+        let outcome = play(&mut console, &config, &mut BinarySearch, &mut rng);
+        assert_eq!(outcome, ComputerOutcome::Inconsistent);
+        assert!(console.output().contains("That can't be right"));
+    }
+}
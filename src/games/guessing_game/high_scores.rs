@@ -0,0 +1,280 @@
+use crate::console::Console;
+use crate::utils;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How many scores the leaderboard shows per range.
+const LEADERBOARD_SIZE: usize = 5;
+
+/// A won round of the guessing game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Score {
+    pub name: String,
+    pub min: i128,
+    pub max: i128,
+    pub attempts: u32,
+    pub elapsed: Duration,
+}
+
+impl Score {
+    /// Fewer attempts win; ties are broken by the faster time.
+    fn rank_key(&self) -> (u32, Duration) {
+        (self.attempts, self.elapsed)
+    }
+
+    /// One tab separated line: name, min, max, attempts, elapsed milliseconds.
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}",
+            self.name,
+            self.min,
+            self.max,
+            self.attempts,
+            self.elapsed.as_millis()
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Score> {
+        let mut fields = line.split('\t');
+        let name = fields.next()?.trim();
+        let min = fields.next()?.parse().ok()?;
+        let max = fields.next()?.parse().ok()?;
+        let attempts = fields.next()?.parse().ok()?;
+        let elapsed = Duration::from_millis(fields.next()?.parse().ok()?);
+        if fields.next().is_some() || name.is_empty() || min > max || attempts == 0 {
+            return None;
+        }
+        Some(Score {
+            name: name.to_string(),
+            min,
+            max,
+            attempts,
+            elapsed,
+        })
+    }
+}
+
+/// High scores kept in a file. Unreadable files and lines are skipped rather than treated as
+/// errors, so a damaged file never stops anyone from playing. Skipped lines are not written
+/// back by [`HighScores::save`], so the game stops saving once it finds any.
+pub struct HighScores {
+    path: PathBuf,
+    scores: Vec<Score>,
+    skipped: usize,
+}
+
+impl HighScores {
+    /// `high_scores.tsv` in the `guessing_game` folder of the user's data directory.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("guessing_game").join("high_scores.tsv"))
+    }
+
+    /// Load the scores stored at `path`. A missing file gives an empty table.
+    pub fn load(path: &Path) -> HighScores {
+        let mut high_scores = HighScores {
+            path: path.to_path_buf(),
+            scores: Vec::new(),
+            skipped: 0,
+        };
+        let contents = match fs::read(path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(_) => return high_scores,
+        };
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            match Score::from_line(line) {
+                Some(score) => high_scores.scores.push(score),
+                None => high_scores.skipped += 1,
+            }
+        }
+        high_scores
+    }
+
+    /// Number of lines that could not be read when loading.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    pub fn scores(&self) -> &[Score] {
+        &self.scores
+    }
+
+    /// Add a score. Tabs and line breaks in the name are replaced by spaces.
+    pub fn record(&mut self, mut score: Score) {
+        score.name = score
+            .name
+            .split(['\t', '\n', '\r'])
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim()
+            .to_string();
+        self.scores.push(score);
+    }
+
+    /// Write every score back to the file, creating its folder if needed.
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut contents = String::new();
+        for score in &self.scores {
+            contents.push_str(&score.to_line());
+            contents.push('\n');
+        }
+        // Write next to the real file first so a crash never leaves it half written.
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(&temp_path, contents)?;
+        fs::rename(&temp_path, &self.path)
+    }
+
+    /// Best score for the range `min..=max`.
+    pub fn best_for_range(&self, min: i128, max: i128) -> Option<&Score> {
+        self.scores
+            .iter()
+            .filter(|score| score.min == min && score.max == max)
+            .min_by_key(|score| score.rank_key())
+    }
+
+    /// Scores grouped by range, each group sorted best first.
+    pub fn by_range(&self) -> BTreeMap<(i128, i128), Vec<&Score>> {
+        let mut ranges: BTreeMap<(i128, i128), Vec<&Score>> = BTreeMap::new();
+        for score in &self.scores {
+            ranges
+                .entry((score.min, score.max))
+                .or_default()
+                .push(score);
+        }
+        for scores in ranges.values_mut() {
+            scores.sort_by_key(|score| score.rank_key());
+        }
+        ranges
+    }
+}
+
+pub fn format_elapsed(elapsed: Duration) -> String {
+    format!("{:.1}s", elapsed.as_secs_f64())
+}
+
+/// Print the top scores of every range.
+pub fn print_leaderboard(console: &mut dyn Console, high_scores: &HighScores) {
+    if high_scores.skipped() > 0 {
+        writeln!(
+            console,
+            "Skipped {} unreadable entries in the high-score file.",
+            high_scores.skipped()
+        );
+    }
+    let ranges = high_scores.by_range();
+    if ranges.is_empty() {
+        writeln!(
+            console,
+            "No high scores yet. Win a game to get on the board!"
+        );
+        return;
+    }
+    for ((min, max), scores) in ranges {
+        writeln!(console, "Range {} to {}", min, max);
+        writeln!(console, "{}", "=".repeat(20));
+        for (i, score) in scores.iter().take(LEADERBOARD_SIZE).enumerate() {
+            writeln!(
+                console,
+                "{}. {} - {} guesses in {}",
+                i + 1,
+                score.name,
+                score.attempts,
+                format_elapsed(score.elapsed)
+            );
+        }
+        writeln!(console);
+    }
+}
+
+pub fn launch(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    writeln!(console, "Guessing Game High Scores\n");
+    match HighScores::default_path() {
+        Some(path) => print_leaderboard(console, &HighScores::load(&path)),
+        None => writeln!(
+            console,
+            "No data directory found, high scores are unavailable."
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("guessing_game_test_{}", std::process::id()))
+            .join(name)
+    }
+
+    fn score(name: &str, max: i128, attempts: u32, millis: u64) -> Score {
+        Score {
+            name: name.to_string(),
+            min: 1,
+            max,
+            attempts,
+            elapsed: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn test_missing_file_is_empty() {
+        let high_scores = HighScores::load(&temp_path("missing.tsv"));
+        assert!(high_scores.scores().is_empty());
+        assert_eq!(high_scores.skipped(), 0);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = temp_path("round_trip.tsv");
+        let mut high_scores = HighScores::load(&path);
+        high_scores.record(score("Ann\tLee", 100, 7, 4200));
+        high_scores.record(score("Bob", 100, 5, 9000));
+        high_scores.record(score("Cy", 10, 3, 1000));
+        high_scores.save().unwrap();
+
+        let loaded = HighScores::load(&path);
+        assert_eq!(loaded.scores().len(), 3);
+        assert_eq!(loaded.scores()[0].name, "Ann Lee");
+        assert_eq!(loaded.best_for_range(1, 100).unwrap().name, "Bob");
+        assert_eq!(loaded.best_for_range(1, 10).unwrap().name, "Cy");
+        assert!(loaded.best_for_range(1, 1000).is_none());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_corrupted_lines_are_skipped() {
+        let path = temp_path("corrupted.tsv");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            b"Ann\t1\t100\t7\t4200\ngarbage\n\xff\xfe\nBob\t1\t100\tx\t1\n",
+        )
+        .unwrap();
+        let high_scores = HighScores::load(&path);
+        assert_eq!(high_scores.scores().len(), 1);
+        assert_eq!(high_scores.skipped(), 3);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_leaderboard_ordering() {
+        let mut high_scores = HighScores::load(&temp_path("unused.tsv"));
+        high_scores.record(score("Slow", 100, 5, 9000));
+        high_scores.record(score("Fast", 100, 5, 1000));
+        high_scores.record(score("Lucky", 100, 1, 5000));
+        let mut console = crate::console::ScriptedConsole::default();
+        print_leaderboard(&mut console, &high_scores);
+        let output = console.output();
+        assert!(output.contains("Range 1 to 100"));
+        assert!(
+            output.contains("1. Lucky - 1 guesses in 5.0s\n2. Fast - 5 guesses in 1.0s\n3. Slow")
+        );
+    }
+}
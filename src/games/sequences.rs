@@ -0,0 +1,516 @@
+use crate::console::Console;
+use crate::menu::Menu;
+use crate::utils;
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+use std::collections::VecDeque;
+
+/// Largest index the explorer will compute a term for.
+pub const MAX_INDEX: usize = 10_000;
+/// Most terms shown for a range or a ratio table.
+pub const MAX_RANGE: usize = 1_000;
+
+/// An integer sequence, indexed from 0.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Sequence {
+    Fibonacci,
+    Lucas,
+    Tribonacci,
+    Pell,
+    Catalan,
+    Primes,
+    Triangular,
+    Recurrence(LinearRecurrence),
+}
+
+impl Sequence {
+    pub const BUILT_IN: [Sequence; 7] = [
+        Sequence::Fibonacci,
+        Sequence::Lucas,
+        Sequence::Tribonacci,
+        Sequence::Pell,
+        Sequence::Catalan,
+        Sequence::Primes,
+        Sequence::Triangular,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Sequence::Fibonacci => "Fibonacci",
+            Sequence::Lucas => "Lucas",
+            Sequence::Tribonacci => "Tribonacci",
+            Sequence::Pell => "Pell",
+            Sequence::Catalan => "Catalan",
+            Sequence::Primes => "Primes",
+            Sequence::Triangular => "Triangular numbers",
+            Sequence::Recurrence(_) => "Custom recurrence",
+        }
+    }
+
+    /// Lazily iterate over every term, starting from index 0.
+    pub fn terms(&self) -> Box<dyn Iterator<Item = BigInt>> {
+        match self {
+            Sequence::Catalan => Box::new(Catalan::new()),
+            Sequence::Primes => Box::new(Primes::new().map(BigInt::from)),
+            Sequence::Triangular => {
+                Box::new((0u64..).map(|n| BigInt::from(u128::from(n) * (u128::from(n) + 1) / 2)))
+            }
+            Sequence::Recurrence(recurrence) => Box::new(recurrence.terms()),
+            _ => Box::new(self.as_recurrence().unwrap().terms()),
+        }
+    }
+
+    /// The built-in sequences that are linear recurrences.
+    fn as_recurrence(&self) -> Option<LinearRecurrence> {
+        let (coefficients, seeds): (&[i64], &[i64]) = match self {
+            Sequence::Fibonacci => (&[1, 1], &[0, 1]),
+            Sequence::Lucas => (&[1, 1], &[2, 1]),
+            Sequence::Tribonacci => (&[1, 1, 1], &[0, 0, 1]),
+            Sequence::Pell => (&[2, 1], &[0, 1]),
+            Sequence::Recurrence(recurrence) => return Some(recurrence.clone()),
+            _ => return None,
+        };
+        LinearRecurrence::new(
+            coefficients.iter().copied().map(BigInt::from).collect(),
+            seeds.iter().copied().map(BigInt::from).collect(),
+        )
+        .ok()
+    }
+
+    /// Where the ratio of consecutive terms tends to, when that is known in closed form.
+    pub fn ratio_limit(&self) -> Option<f64> {
+        match self {
+            Sequence::Fibonacci | Sequence::Lucas => Some((1.0 + 5f64.sqrt()) / 2.0),
+            // The real root of x^3 = x^2 + x + 1
+            Sequence::Tribonacci => Some(1.839_286_755_214_161),
+            Sequence::Pell => Some(1.0 + 2f64.sqrt()),
+            Sequence::Catalan => Some(4.0),
+            Sequence::Triangular => Some(1.0),
+            Sequence::Primes | Sequence::Recurrence(_) => None,
+        }
+    }
+
+    /// The term at `index`.
+    pub fn nth(&self, index: usize) -> BigInt {
+        self.terms().nth(index).expect("sequences are infinite")
+    }
+
+    /// The terms from `first` to `last`, both included.
+    pub fn range(&self, first: usize, last: usize) -> Vec<BigInt> {
+        self.terms()
+            .skip(first)
+            .take((last + 1).saturating_sub(first))
+            .collect()
+    }
+
+    /// The ratio of each of the first `count` terms after index 0 to the one before it.
+    /// Terms following a zero have no ratio and are skipped.
+    pub fn ratios(&self, count: usize) -> Vec<(usize, f64)> {
+        let terms: Vec<BigInt> = self.terms().take(count + 1).collect();
+        terms
+            .windows(2)
+            .enumerate()
+            .filter_map(|(i, pair)| ratio(&pair[1], &pair[0]).map(|value| (i + 1, value)))
+            .collect()
+    }
+}
+
+/// `numerator / denominator` as a float, even when both are far beyond `f64` range.
+fn ratio(numerator: &BigInt, denominator: &BigInt) -> Option<f64> {
+    if denominator.is_zero() {
+        return None;
+    }
+    let bits = numerator.bits().max(denominator.bits());
+    let shift = bits.saturating_sub(f64::MANTISSA_DIGITS as u64 + 8);
+    Some((numerator >> shift).to_f64()? / (denominator >> shift).to_f64()?)
+}
+
+/// a(n) = c1·a(n-1) + c2·a(n-2) + … + ck·a(n-k), starting from k seed terms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearRecurrence {
+    coefficients: Vec<BigInt>,
+    seeds: Vec<BigInt>,
+}
+
+impl LinearRecurrence {
+    pub fn new(coefficients: Vec<BigInt>, seeds: Vec<BigInt>) -> Result<Self, String> {
+        if coefficients.is_empty() {
+            return Err("A recurrence needs at least one coefficient".to_string());
+        }
+        if coefficients.len() != seeds.len() {
+            return Err(format!(
+                "{} coefficients need {} seed terms, got {}",
+                coefficients.len(),
+                coefficients.len(),
+                seeds.len()
+            ));
+        }
+        Ok(LinearRecurrence {
+            coefficients,
+            seeds,
+        })
+    }
+
+    /// Parse `"<coefficients>; <seeds>"`, both comma separated. `"1,1; 0,1"` is Fibonacci.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let Some((coefficients, seeds)) = text.split_once(';') else {
+            return Err(
+                "Separate the coefficients from the seeds with ';', e.g. \"1,1; 0,1\"".to_string(),
+            );
+        };
+        let parse_list = |list: &str| {
+            list.split(',')
+                .map(|item| {
+                    item.trim()
+                        .parse::<BigInt>()
+                        .map_err(|_| format!("\"{}\" is not an integer", item.trim()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        LinearRecurrence::new(parse_list(coefficients)?, parse_list(seeds)?)
+    }
+
+    pub fn terms(&self) -> RecurrenceTerms {
+        RecurrenceTerms {
+            coefficients: self.coefficients.clone(),
+            window: self.seeds.iter().cloned().collect(),
+        }
+    }
+}
+
+/// Iterator over the terms of a [`LinearRecurrence`].
+pub struct RecurrenceTerms {
+    coefficients: Vec<BigInt>,
+    // The next k terms, oldest first
+    window: VecDeque<BigInt>,
+}
+
+impl Iterator for RecurrenceTerms {
+    type Item = BigInt;
+
+    fn next(&mut self) -> Option<BigInt> {
+        let following: BigInt = self
+            .coefficients
+            .iter()
+            .zip(self.window.iter().rev())
+            .map(|(coefficient, term)| coefficient * term)
+            .sum();
+        self.window.push_back(following);
+        self.window.pop_front()
+    }
+}
+
+/// Iterator over the Catalan numbers 1, 1, 2, 5, 14, …
+pub struct Catalan {
+    index: u64,
+    current: BigInt,
+}
+
+impl Catalan {
+    pub fn new() -> Self {
+        Catalan {
+            index: 0,
+            current: BigInt::from(1),
+        }
+    }
+}
+
+impl Default for Catalan {
+    fn default() -> Self {
+        Catalan::new()
+    }
+}
+
+impl Iterator for Catalan {
+    type Item = BigInt;
+
+    fn next(&mut self) -> Option<BigInt> {
+        // C(n+1) = C(n) · 2(2n+1) / (n+2), which always divides exactly
+        let n = self.index;
+        let following = &self.current * (2 * (2 * n + 1)) / (n + 2);
+        self.index += 1;
+        Some(std::mem::replace(&mut self.current, following))
+    }
+}
+
+/// Iterator over the primes 2, 3, 5, 7, …, found by trial division by the primes so far.
+#[derive(Default)]
+pub struct Primes {
+    found: Vec<u64>,
+}
+
+impl Primes {
+    pub fn new() -> Self {
+        Primes { found: Vec::new() }
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let mut candidate = match self.found.last() {
+            None => 2,
+            Some(2) => 3,
+            Some(last) => last + 2,
+        };
+        while self
+            .found
+            .iter()
+            .take_while(|&&prime| prime * prime <= candidate)
+            .any(|&prime| candidate.is_multiple_of(prime))
+        {
+            candidate += 2;
+        }
+        self.found.push(candidate);
+        Some(candidate)
+    }
+}
+
+/// Parse a single index, rejecting anything above `MAX_INDEX`.
+pub fn parse_index(text: &str) -> Result<usize, String> {
+    match text.trim().parse::<usize>() {
+        Ok(index) if index <= MAX_INDEX => Ok(index),
+        Ok(_) => Err(format!("Please keep indices at most {}", MAX_INDEX)),
+        Err(_) => Err(format!("\"{}\" is not a valid index", text.trim())),
+    }
+}
+
+/// Parse an index, or a range written `first..last` or `first-last`.
+pub fn parse_range(text: &str) -> Result<(usize, usize), String> {
+    let (first, last) = match text.split_once("..").or_else(|| text.split_once('-')) {
+        Some((first, last)) => (parse_index(first)?, parse_index(last)?),
+        None => (0, parse_index(text)?),
+    };
+    if first > last {
+        return Err(format!("{} comes after {}", first, last));
+    }
+    if last - first >= MAX_RANGE {
+        return Err(format!("Please ask for at most {} terms", MAX_RANGE));
+    }
+    Ok((first, last))
+}
+
+/// Let the user pick a sequence, or define their own recurrence. Returns `None` to go back.
+pub fn choose_sequence(console: &mut dyn Console) -> Option<Sequence> {
+    loop {
+        writeln!(console, "Select a sequence:");
+        for (i, sequence) in Sequence::BUILT_IN.iter().enumerate() {
+            writeln!(console, "{}. {}", i + 1, sequence.name());
+        }
+        writeln!(
+            console,
+            "{}. Custom linear recurrence",
+            Sequence::BUILT_IN.len() + 1
+        );
+        writeln!(console, "0. Back");
+        let selection = console.read_line()?;
+        match selection.trim().parse::<usize>() {
+            Ok(0) => return None,
+            Ok(num) if num <= Sequence::BUILT_IN.len() => {
+                return Some(Sequence::BUILT_IN[num - 1].clone())
+            }
+            Ok(num) if num == Sequence::BUILT_IN.len() + 1 => loop {
+                writeln!(
+                    console,
+                    "Enter the coefficients and seeds, e.g. \"1,1; 0,1\" for a(n) = a(n-1) + a(n-2) from 0, 1:"
+                );
+                match LinearRecurrence::parse(&console.read_line()?) {
+                    Ok(recurrence) => return Some(Sequence::Recurrence(recurrence)),
+                    Err(message) => writeln!(console, "{}", message),
+                }
+            },
+            _ => writeln!(console, "Invalid selection"),
+        }
+    }
+}
+
+/// Ask for a sequence, then repeatedly for input that `show` answers.
+fn explore(
+    console: &mut dyn Console,
+    prompt: &str,
+    show: fn(&mut dyn Console, &Sequence, &str) -> Result<(), String>,
+) {
+    utils::clear_screen(console);
+    let Some(sequence) = choose_sequence(console) else {
+        return;
+    };
+    writeln!(console, "{}", sequence.name());
+    loop {
+        writeln!(console, "{}, or press Enter to exit:", prompt);
+        let input = utils::read_input(console);
+        if input.is_empty() {
+            break;
+        }
+        if let Err(message) = show(console, &sequence, &input) {
+            writeln!(console, "{}", message);
+        }
+    }
+}
+
+fn show_range(console: &mut dyn Console, sequence: &Sequence, input: &str) -> Result<(), String> {
+    let (first, last) = parse_range(input)?;
+    let terms: Vec<String> = sequence
+        .range(first, last)
+        .iter()
+        .map(|term| term.to_string())
+        .collect();
+    writeln!(console, "{}", terms.join(" "));
+    Ok(())
+}
+
+fn show_nth(console: &mut dyn Console, sequence: &Sequence, input: &str) -> Result<(), String> {
+    let index = parse_index(input)?;
+    writeln!(console, "a({}) = {}", index, sequence.nth(index));
+    Ok(())
+}
+
+fn show_ratios(console: &mut dyn Console, sequence: &Sequence, input: &str) -> Result<(), String> {
+    let count = parse_index(input)?;
+    if count >= MAX_RANGE {
+        return Err(format!("Please ask for at most {} ratios", MAX_RANGE - 1));
+    }
+    let limit = sequence.ratio_limit();
+    match limit {
+        Some(_) => writeln!(console, "{:>6} {:>20} {:>12}", "n", "a(n)/a(n-1)", "error"),
+        None => writeln!(console, "{:>6} {:>20}", "n", "a(n)/a(n-1)"),
+    }
+    for (index, value) in sequence.ratios(count) {
+        match limit {
+            Some(limit) => writeln!(
+                console,
+                "{:>6} {:>20.15} {:>12.3e}",
+                index,
+                value,
+                value - limit
+            ),
+            None => writeln!(console, "{:>6} {:>20.15}", index, value),
+        }
+    }
+    if let Some(limit) = limit {
+        writeln!(console, "Limit: {:.15}", limit);
+    }
+    Ok(())
+}
+
+pub fn menu() -> Menu<'static> {
+    Menu::new("Sequence Explorer")
+        .entry("Terms in a range", |console| {
+            explore(console, "Enter a range such as 10..20", show_range)
+        })
+        .entry("Nth term", |console| {
+            explore(console, "Enter an index", show_nth)
+        })
+        .entry("Ratio convergence", |console| {
+            explore(console, "Enter the number of ratios", show_ratios)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+    use crate::games::fibonacci;
+
+    fn first(sequence: &Sequence, count: usize) -> Vec<i64> {
+        sequence
+            .terms()
+            .take(count)
+            .map(|term| term.to_i64().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_built_in_sequences() {
+        assert_eq!(
+            first(&Sequence::Fibonacci, 10),
+            vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]
+        );
+        assert_eq!(first(&Sequence::Lucas, 8), vec![2, 1, 3, 4, 7, 11, 18, 29]);
+        assert_eq!(
+            first(&Sequence::Tribonacci, 9),
+            vec![0, 0, 1, 1, 2, 4, 7, 13, 24]
+        );
+        assert_eq!(first(&Sequence::Pell, 7), vec![0, 1, 2, 5, 12, 29, 70]);
+        assert_eq!(
+            first(&Sequence::Catalan, 10),
+            vec![1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862]
+        );
+        assert_eq!(
+            first(&Sequence::Primes, 10),
+            vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]
+        );
+        assert_eq!(first(&Sequence::Triangular, 6), vec![0, 1, 3, 6, 10, 15]);
+    }
+
+    #[test]
+    fn test_nth_and_range() {
+        assert_eq!(
+            Sequence::Fibonacci.nth(500).to_string(),
+            fibonacci::fibonacci(500).to_string()
+        );
+        assert_eq!(Sequence::Primes.nth(999), BigInt::from(7919));
+        assert_eq!(
+            Sequence::Triangular.range(3, 5),
+            vec![BigInt::from(6), BigInt::from(10), BigInt::from(15)]
+        );
+        assert!(Sequence::Lucas.range(5, 4).is_empty());
+    }
+
+    #[test]
+    fn test_custom_recurrence() {
+        // Jacobsthal numbers: a(n) = a(n-1) + 2a(n-2)
+        let recurrence = LinearRecurrence::parse("1, 2; 0, 1").unwrap();
+        assert_eq!(
+            first(&Sequence::Recurrence(recurrence), 8),
+            vec![0, 1, 1, 3, 5, 11, 21, 43]
+        );
+        let alternating = LinearRecurrence::parse("-1; 1").unwrap();
+        assert_eq!(
+            first(&Sequence::Recurrence(alternating), 4),
+            vec![1, -1, 1, -1]
+        );
+        assert!(LinearRecurrence::parse("1,1; 0").is_err());
+        assert!(LinearRecurrence::parse("1,1").is_err());
+        assert!(LinearRecurrence::parse("1,x; 0,1").is_err());
+    }
+
+    #[test]
+    fn test_ratios_converge() {
+        for sequence in [Sequence::Fibonacci, Sequence::Pell, Sequence::Tribonacci] {
+            let (_, last) = *sequence.ratios(2000).last().unwrap();
+            assert!((last - sequence.ratio_limit().unwrap()).abs() < 1e-12);
+        }
+        // The ratio after F(0) = 0 is undefined
+        assert_eq!(Sequence::Fibonacci.ratios(3), vec![(2, 1.0), (3, 2.0)]);
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("10..20"), Ok((10, 20)));
+        assert_eq!(parse_range("3-4"), Ok((3, 4)));
+        assert_eq!(parse_range("7"), Ok((0, 7)));
+        assert!(parse_range("5..1").is_err());
+        assert!(parse_range("0..5000").is_err());
+        assert!(parse_range("x").is_err());
+    }
+
+    #[test]
+    fn test_single_index_commands_reject_ranges() {
+        let mut console = ScriptedConsole::new([
+            "2", "1", "10..20", "7", "", "", "3", "1", "1-4", "", "", "0",
+        ]);
+        menu().run(&mut console);
+        let output = console.output();
+        assert!(output.contains("\"10..20\" is not a valid index"));
+        assert!(output.contains("\"1-4\" is not a valid index"));
+        assert!(output.contains("a(7) = 13"));
+    }
+
+    #[test]
+    fn test_range_session() {
+        let mut console = ScriptedConsole::new(["1", "2", "0..5", "", "", "0"]);
+        menu().run(&mut console);
+        assert!(console.output().contains("\n2 1 3 4 7 11\n"));
+    }
+}
@@ -0,0 +1,439 @@
+pub mod karaoke;
+
+use crate::console::Console;
+use crate::utils;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// Song definitions shipped with the program.
+const BUILT_IN: [&str; 3] = [
+    include_str!("songs/twelve_days.toml"),
+    include_str!("songs/house_that_jack_built.toml"),
+    include_str!("songs/old_macdonald.toml"),
+];
+
+/// One line of a verse.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerseLine {
+    pub text: String,
+    /// Whether the line is about the item the verse introduces.
+    pub newest: bool,
+}
+
+/// A cumulative song: verse n introduces item n, then runs back through every item so far.
+///
+/// Lines are templates in which `{field}` is replaced by that field of the item the line is
+/// about, and `{newer.field}` by the field of the item introduced right after it.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Song {
+    name: String,
+    title: String,
+    /// Lines opening each verse, about the verse's newest item.
+    #[serde(default)]
+    intro: Vec<String>,
+    /// Lines for the newest item, when they differ from `cumulative`.
+    #[serde(default)]
+    newest: Vec<String>,
+    /// Lines repeated for every item, newest first.
+    cumulative: Vec<String>,
+    /// Lines for the first item once later items precede it, when they differ from `cumulative`.
+    #[serde(default)]
+    first: Vec<String>,
+    /// Lines closing each verse, about the verse's newest item.
+    #[serde(default)]
+    outro: Vec<String>,
+    /// Appended to the last line of each item except the final one in a verse.
+    #[serde(default)]
+    separator: String,
+    /// Appended to the last line about the first item.
+    #[serde(default)]
+    verse_end: String,
+    /// Replaces `verse_end` in the last verse.
+    final_verse_end: Option<String>,
+    /// Start every item line with a capital letter.
+    #[serde(default)]
+    capitalize: bool,
+    items: Vec<BTreeMap<String, String>>,
+}
+
+impl Song {
+    pub fn from_toml(text: &str) -> Result<Song, String> {
+        let song: Song = toml::from_str(text).map_err(|err| err.message().to_string())?;
+        song.validate()
+    }
+
+    pub fn from_json(text: &str) -> Result<Song, String> {
+        let song: Song = serde_json::from_str(text).map_err(|err| err.to_string())?;
+        song.validate()
+    }
+
+    /// Load a `.toml` or `.json` song definition.
+    pub fn load(path: &Path) -> Result<Song, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+        let song = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Song::from_toml(&text),
+            Some("json") => Song::from_json(&text),
+            _ => return Err(format!("{} is not a .toml or .json file", path.display())),
+        };
+        song.map_err(|message| format!("{}: {}", path.display(), message))
+    }
+
+    /// Render every verse once, so a song that loads can always be sung.
+    fn validate(self) -> Result<Song, String> {
+        if self.items.is_empty() {
+            return Err("A song needs at least one item".to_string());
+        }
+        if self.cumulative.is_empty() {
+            return Err("A song needs at least one cumulative line".to_string());
+        }
+        for number in 1..=self.items.len() {
+            self.render(number)?;
+        }
+        Ok(self)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Field `name` of the item introduced in verse `number`, counting from 1.
+    pub fn item_field(&self, number: usize, name: &str) -> Option<&str> {
+        self.items
+            .get(number.checked_sub(1)?)?
+            .get(name)
+            .map(String::as_str)
+    }
+
+    pub fn verse_count(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Verse `number`, counting from 1, one line per line and ending in a newline.
+    pub fn verse(&self, number: usize) -> Option<String> {
+        let lines: Vec<String> = self
+            .verse_lines(number)?
+            .into_iter()
+            .map(|line| line.text)
+            .collect();
+        Some(lines.join("\n") + "\n")
+    }
+
+    /// The lines of verse `number`, counting from 1.
+    pub fn verse_lines(&self, number: usize) -> Option<Vec<VerseLine>> {
+        if !(1..=self.verse_count()).contains(&number) {
+            return None;
+        }
+        Some(self.render(number).expect("validated when loaded"))
+    }
+
+    pub fn verses(&self) -> Vec<String> {
+        (1..=self.verse_count())
+            .filter_map(|number| self.verse(number))
+            .collect()
+    }
+
+    fn render(&self, number: usize) -> Result<Vec<VerseLine>, String> {
+        let newest = number - 1;
+        let mut lines = Vec::new();
+        for template in &self.intro {
+            lines.push(VerseLine {
+                text: fill(template, &self.items[newest], None)?,
+                newest: false,
+            });
+        }
+        for index in (0..number).rev() {
+            let templates = if index == newest && !self.newest.is_empty() {
+                &self.newest
+            } else if index == 0 && number > 1 && !self.first.is_empty() {
+                &self.first
+            } else {
+                &self.cumulative
+            };
+            let newer = (index < newest).then(|| &self.items[index + 1]);
+            for template in templates {
+                let mut line = fill(template, &self.items[index], newer)?;
+                if self.capitalize {
+                    line = capitalize(&line);
+                }
+                lines.push(VerseLine {
+                    text: line,
+                    newest: index == newest,
+                });
+            }
+            let ending = match index {
+                0 if number == self.verse_count() => {
+                    self.final_verse_end.as_deref().unwrap_or(&self.verse_end)
+                }
+                0 => &self.verse_end,
+                _ => &self.separator,
+            };
+            if let Some(last) = lines.last_mut() {
+                last.text.push_str(ending);
+            }
+        }
+        for template in &self.outro {
+            lines.push(VerseLine {
+                text: fill(template, &self.items[newest], None)?,
+                newest: false,
+            });
+        }
+        Ok(lines)
+    }
+}
+
+/// Replace the `{field}` and `{newer.field}` placeholders in `template`.
+fn fill(
+    template: &str,
+    item: &BTreeMap<String, String>,
+    newer: Option<&BTreeMap<String, String>>,
+) -> Result<String, String> {
+    let mut line = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        line.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            return Err(format!("Unclosed placeholder in \"{}\"", template));
+        };
+        let placeholder = &rest[start + 1..start + end];
+        let value = match placeholder.strip_prefix("newer.") {
+            // The newest item has no newer one; such lines belong in `newest` instead.
+            Some(field) => newer.and_then(|newer| newer.get(field)),
+            None => item.get(placeholder),
+        };
+        match value {
+            Some(value) => line.push_str(value),
+            None => {
+                return Err(format!(
+                    "No value for {{{}}} in \"{}\"",
+                    placeholder, template
+                ))
+            }
+        }
+        rest = &rest[start + end + 1..];
+    }
+    line.push_str(rest);
+    Ok(line)
+}
+
+fn capitalize(line: &str) -> String {
+    let mut chars = line.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The songs shipped with the program.
+pub fn built_in() -> Vec<Song> {
+    BUILT_IN
+        .iter()
+        .map(|text| Song::from_toml(text).expect("built-in songs are valid"))
+        .collect()
+}
+
+/// A built-in song by name or title, ignoring case, or else a song file at that path.
+pub fn find(name: &str) -> Result<Song, String> {
+    let wanted = name.trim();
+    match built_in().into_iter().find(|song| {
+        song.name.eq_ignore_ascii_case(wanted) || song.title.eq_ignore_ascii_case(wanted)
+    }) {
+        Some(song) => Ok(song),
+        None if Path::new(wanted).is_file() => Song::load(Path::new(wanted)),
+        None => {
+            let names: Vec<String> = built_in().iter().map(|song| song.name.clone()).collect();
+            Err(format!(
+                "Unknown song \"{}\". Choose one of {} or give a .toml or .json file",
+                wanted,
+                names.join(", ")
+            ))
+        }
+    }
+}
+
+/// Parse the verses to sing out of `count`: empty for all of them, a single verse number,
+/// or a range written `first..last` or `first-last`.
+pub fn parse_verses(text: &str, count: usize) -> Result<RangeInclusive<usize>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(1..=count);
+    }
+    let parse_number = |number: &str| match number.trim().parse::<usize>() {
+        Ok(number) if (1..=count).contains(&number) => Ok(number),
+        _ => Err(format!(
+            "\"{}\" is not a verse number from 1 to {}",
+            number.trim(),
+            count
+        )),
+    };
+    let (first, last) = match text.split_once("..").or_else(|| text.split_once('-')) {
+        Some((first, last)) => (parse_number(first)?, parse_number(last)?),
+        None => {
+            let number = parse_number(text)?;
+            (number, number)
+        }
+    };
+    if first > last {
+        return Err(format!("Verse {} comes after verse {}", first, last));
+    }
+    Ok(first..=last)
+}
+
+pub fn print_verses(console: &mut dyn Console, song: &Song, verses: RangeInclusive<usize>) {
+    for number in verses {
+        if let Some(verse) = song.verse(number) {
+            writeln!(console, "{}", verse);
+        }
+    }
+}
+
+/// Let the user pick a built-in song or load one from a file. Returns `None` to go back.
+pub fn choose_song(console: &mut dyn Console) -> Option<Song> {
+    let songs = built_in();
+    loop {
+        writeln!(console, "Select a song:");
+        for (i, song) in songs.iter().enumerate() {
+            writeln!(console, "{}. {}", i + 1, song.title());
+        }
+        writeln!(console, "{}. Load a song file", songs.len() + 1);
+        writeln!(console, "0. Back");
+        let selection = console.read_line()?;
+        match selection.trim().parse::<usize>() {
+            Ok(0) => return None,
+            Ok(num) if num <= songs.len() => return Some(songs[num - 1].clone()),
+            Ok(num) if num == songs.len() + 1 => {
+                writeln!(console, "Enter the path of a .toml or .json song file:");
+                match Song::load(Path::new(console.read_line()?.trim())) {
+                    Ok(song) => return Some(song),
+                    Err(message) => writeln!(console, "{}", message),
+                }
+            }
+            _ => writeln!(console, "Invalid selection"),
+        }
+    }
+}
+
+pub fn launch(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    let Some(song) = choose_song(console) else {
+        return;
+    };
+    writeln!(console, "{}\n", song.title());
+    loop {
+        writeln!(
+            console,
+            "Enter a verse (1-{}), a range such as 2..4, \"all\", or press Enter to exit:",
+            song.verse_count()
+        );
+        let input = utils::read_input(console);
+        if input.is_empty() {
+            break;
+        }
+        let input = if input.eq_ignore_ascii_case("all") {
+            ""
+        } else {
+            &input
+        };
+        match parse_verses(input, song.verse_count()) {
+            Ok(verses) => print_verses(console, &song, verses),
+            Err(message) => writeln!(console, "{}", message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+
+    #[test]
+    fn test_built_in_songs() {
+        let songs = built_in();
+        let names: Vec<&str> = songs.iter().map(|song| song.name()).collect();
+        assert_eq!(
+            names,
+            vec!["twelve-days", "house-that-jack-built", "old-macdonald"]
+        );
+        assert_eq!(songs[0].verse_count(), 12);
+        assert_eq!(songs[1].verse_count(), 12);
+    }
+
+    #[test]
+    fn test_house_that_jack_built() {
+        let song = find("House-That-Jack-Built").unwrap();
+        assert_eq!(
+            song.verse(1).unwrap(),
+            "This is the house that Jack built.\n"
+        );
+        assert_eq!(
+            song.verse(3).unwrap(),
+            "This is the rat\nThat ate the malt\nThat lay in the house that Jack built.\n"
+        );
+    }
+
+    #[test]
+    fn test_old_macdonald() {
+        let song = find("Old MacDonald Had a Farm").unwrap();
+        assert_eq!(
+            song.verse(2).unwrap(),
+            "Old MacDonald had a farm, E-I-E-I-O!\n\
+             And on that farm he had a pig, E-I-E-I-O!\n\
+             With an oink oink here and an oink oink there,\n\
+             Here an oink, there an oink, everywhere an oink oink,\n\
+             With a moo moo here and a moo moo there,\n\
+             Here a moo, there a moo, everywhere a moo moo.\n\
+             Old MacDonald had a farm, E-I-E-I-O!\n"
+        );
+    }
+
+    #[test]
+    fn test_json_song_and_errors() {
+        let song = Song::from_json(
+            r#"{"name": "count", "title": "Counting", "cumulative": ["{n}"],
+                "separator": ",", "verse_end": ".", "items": [{"n": "one"}, {"n": "two"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(song.verses(), vec!["one.\n", "two,\none.\n"]);
+        assert_eq!(song.verse(3), None);
+
+        let missing = Song::from_toml(
+            "name = \"x\"\ntitle = \"X\"\ncumulative = [\"{gift}\"]\n[[items]]\nthing = \"a\"",
+        );
+        assert_eq!(
+            missing,
+            Err("No value for {gift} in \"{gift}\"".to_string())
+        );
+        let no_items =
+            Song::from_toml("name = \"x\"\ntitle = \"X\"\ncumulative = [\"a\"]\nitems = []");
+        assert!(no_items.is_err());
+        assert!(find("no such song").is_err());
+    }
+
+    #[test]
+    fn test_parse_verses() {
+        assert_eq!(parse_verses("", 12), Ok(1..=12));
+        assert_eq!(parse_verses("5", 12), Ok(5..=5));
+        assert_eq!(parse_verses("2..4", 12), Ok(2..=4));
+        assert_eq!(parse_verses("3-6", 12), Ok(3..=6));
+        assert!(parse_verses("0", 12).is_err());
+        assert!(parse_verses("13", 12).is_err());
+        assert!(parse_verses("4..2", 12).is_err());
+    }
+
+    #[test]
+    fn test_song_session() {
+        let mut console = ScriptedConsole::new(["2", "1..2", ""]);
+        launch(&mut console);
+        assert!(console
+            .output()
+            .contains("This is the house that Jack built.\n\nThis is the malt\n"));
+    }
+}
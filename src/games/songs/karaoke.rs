@@ -0,0 +1,287 @@
+use super::{print_verses, Song};
+use crate::console::Console;
+use crate::utils;
+use std::io::{self, Read, Write};
+use std::ops::RangeInclusive;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use termion::raw::IntoRawMode;
+use termion::{clear, color, style};
+
+pub const DEFAULT_TEMPO: u32 = 90;
+pub const MIN_TEMPO: u32 = 10;
+pub const MAX_TEMPO: u32 = 600;
+
+/// How long each wait for a key lasts while playback is paused.
+const PAUSED_WAIT: Duration = Duration::from_secs(1);
+
+/// Parse a tempo in lines per minute. An empty answer picks `DEFAULT_TEMPO`.
+pub fn parse_tempo(text: &str) -> Result<u32, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(DEFAULT_TEMPO);
+    }
+    match text.parse::<u32>() {
+        Ok(tempo) if (MIN_TEMPO..=MAX_TEMPO).contains(&tempo) => Ok(tempo),
+        _ => Err(format!(
+            "Please enter a tempo from {} to {} lines per minute",
+            MIN_TEMPO, MAX_TEMPO
+        )),
+    }
+}
+
+/// Time between two lines at `tempo` lines per minute.
+pub fn line_delay(tempo: u32) -> Duration {
+    Duration::from_secs(60) / tempo.max(1)
+}
+
+/// What a key press asks the player to do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    TogglePause,
+    SkipVerse,
+    Quit,
+}
+
+/// Source of key presses during playback.
+pub trait Controls {
+    /// Wait up to `timeout` for a key press.
+    fn wait(&mut self, timeout: Duration) -> Option<Control>;
+}
+
+/// How the song ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Playback {
+    Finished,
+    Quit,
+}
+
+/// Reveal `verses` of `song` one line at a time, highlighting the lines about each verse's new
+/// item. Output uses `\r\n` line endings so it can be written to a terminal in raw mode.
+pub fn play(
+    out: &mut dyn Write,
+    controls: &mut dyn Controls,
+    song: &Song,
+    verses: RangeInclusive<usize>,
+    tempo: u32,
+) -> io::Result<Playback> {
+    let delay = line_delay(tempo);
+    write!(
+        out,
+        "{}{}{}\r\nSpace: pause/resume   s: skip verse   q: quit\r\n\r\n",
+        style::Bold,
+        song.title(),
+        style::Reset
+    )?;
+    for number in verses {
+        let Some(lines) = song.verse_lines(number) else {
+            continue;
+        };
+        'verse: for line in lines {
+            if line.newest {
+                write!(
+                    out,
+                    "{}{}{}{}{}\r\n",
+                    style::Bold,
+                    color::Fg(color::Yellow),
+                    line.text,
+                    color::Fg(color::Reset),
+                    style::Reset
+                )?;
+            } else {
+                write!(out, "{}\r\n", line.text)?;
+            }
+            out.flush()?;
+
+            match controls.wait(delay) {
+                None => {}
+                Some(Control::Quit) => return Ok(Playback::Quit),
+                Some(Control::SkipVerse) => break 'verse,
+                Some(Control::TogglePause) => {
+                    write!(out, "-- paused --")?;
+                    out.flush()?;
+                    let resumed = loop {
+                        match controls.wait(PAUSED_WAIT) {
+                            None => continue,
+                            Some(control) => break control,
+                        }
+                    };
+                    write!(out, "\r{}", clear::CurrentLine)?;
+                    match resumed {
+                        Control::Quit => return Ok(Playback::Quit),
+                        Control::SkipVerse => break 'verse,
+                        Control::TogglePause => {}
+                    }
+                }
+            }
+        }
+        write!(out, "\r\n")?;
+    }
+    Ok(Playback::Finished)
+}
+
+/// Key presses read from the terminal by a background thread.
+struct TerminalControls {
+    bytes: mpsc::Receiver<u8>,
+}
+
+impl TerminalControls {
+    fn spawn() -> io::Result<Self> {
+        let mut tty = termion::get_tty()?;
+        let (send, bytes) = mpsc::channel();
+        // The thread stops after reading a `q`, so playback always ends with one. A thread
+        // left blocked on the terminal would swallow the next key typed at the menu.
+        thread::spawn(move || {
+            // One byte at a time: anything read past the `q` would be lost to the menu.
+            let mut byte = [0];
+            while let Ok(1) = tty.read(&mut byte) {
+                if send.send(byte[0]).is_err() || byte[0] == b'q' {
+                    return;
+                }
+            }
+        });
+        Ok(TerminalControls { bytes })
+    }
+}
+
+impl Controls for TerminalControls {
+    fn wait(&mut self, timeout: Duration) -> Option<Control> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.bytes.recv_timeout(remaining) {
+                Ok(b' ' | b'p') => return Some(Control::TogglePause),
+                Ok(b's' | b'n') => return Some(Control::SkipVerse),
+                Ok(b'q') | Err(RecvTimeoutError::Disconnected) => return Some(Control::Quit),
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => return None,
+            }
+        }
+    }
+}
+
+/// Sing `verses` of `song` on the terminal, or print them plainly when the console is not an
+/// interactive terminal.
+pub fn sing(console: &mut dyn Console, song: &Song, verses: RangeInclusive<usize>, tempo: u32) {
+    if !console.is_terminal() {
+        print_verses(console, song, verses);
+        return;
+    }
+    utils::clear_screen(console);
+    let result = io::stdout().into_raw_mode().and_then(|mut out| {
+        let mut controls = TerminalControls::spawn()?;
+        let playback = play(&mut out, &mut controls, song, verses, tempo)?;
+        if playback == Playback::Finished {
+            write!(out, "Press q to finish\r\n")?;
+            out.flush()?;
+            while controls.wait(PAUSED_WAIT) != Some(Control::Quit) {}
+        }
+        Ok(playback)
+    });
+    match result {
+        Ok(Playback::Finished) => {}
+        Ok(Playback::Quit) => writeln!(console, "Stopped."),
+        Err(err) => writeln!(console, "Karaoke needs a terminal: {}", err),
+    }
+}
+
+pub fn launch(console: &mut dyn Console, song: &Song) {
+    utils::clear_screen(console);
+    writeln!(console, "Karaoke: {}", song.title());
+    let tempo = loop {
+        writeln!(
+            console,
+            "Enter the tempo in lines per minute, or press Enter for {}:",
+            DEFAULT_TEMPO
+        );
+        let Some(input) = console.read_line() else {
+            return;
+        };
+        match parse_tempo(&input) {
+            Ok(tempo) => break tempo,
+            Err(message) => writeln!(console, "{}", message),
+        }
+    };
+    sing(console, song, 1..=song.verse_count(), tempo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+    use crate::games::songs;
+    use std::collections::VecDeque;
+
+    /// Answers each wait with the next scripted control, without sleeping.
+    struct ScriptedControls(VecDeque<Option<Control>>);
+
+    impl Controls for ScriptedControls {
+        fn wait(&mut self, _timeout: Duration) -> Option<Control> {
+            self.0.pop_front().flatten()
+        }
+    }
+
+    fn play_with(
+        controls: Vec<Option<Control>>,
+        verses: RangeInclusive<usize>,
+    ) -> (Playback, String) {
+        let song = songs::find("twelve-days").unwrap();
+        let mut out = Vec::new();
+        let mut controls = ScriptedControls(controls.into());
+        let playback = play(&mut out, &mut controls, &song, verses, 600).unwrap();
+        (playback, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_tempo() {
+        assert_eq!(parse_tempo(""), Ok(DEFAULT_TEMPO));
+        assert_eq!(parse_tempo(" 120 "), Ok(120));
+        assert!(parse_tempo("5").is_err());
+        assert!(parse_tempo("fast").is_err());
+        assert_eq!(line_delay(120), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_play_highlights_new_gift() {
+        let (playback, out) = play_with(Vec::new(), 2..=2);
+        assert_eq!(playback, Playback::Finished);
+        let highlighted = format!(
+            "{}{}Two turtle doves,",
+            style::Bold,
+            color::Fg(color::Yellow)
+        );
+        assert!(out.contains(&highlighted));
+        assert!(out.contains("\r\nAnd a partridge in a pear tree.\r\n"));
+    }
+
+    #[test]
+    fn test_skip_pause_and_quit() {
+        // Skip verse 1 after its first line, pause and resume in verse 2, then quit
+        let controls = vec![
+            Some(Control::SkipVerse),
+            None,
+            Some(Control::TogglePause),
+            None,
+            Some(Control::TogglePause),
+            Some(Control::Quit),
+        ];
+        let (playback, out) = play_with(controls, 1..=3);
+        assert_eq!(playback, Playback::Quit);
+        assert!(!out.contains("A partridge in a pear tree."));
+        assert!(out.contains("-- paused --"));
+        assert!(!out.contains("third day"));
+    }
+
+    #[test]
+    fn test_plain_output_without_terminal() {
+        let song = songs::find("twelve-days").unwrap();
+        let mut console = ScriptedConsole::new(["abc", "240"]);
+        launch(&mut console, &song);
+        assert!(console.output().contains("Please enter a tempo"));
+        assert!(console
+            .output()
+            .contains("my true love gave to me\nA partridge in a pear tree.\n"));
+        assert!(!console.output().contains('\r'));
+    }
+}
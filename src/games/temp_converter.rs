@@ -1,37 +1,329 @@
+use crate::console::Console;
 use crate::utils;
+use regex::Regex;
+use std::fmt;
 
-pub fn launch() {
-    utils::clear_screen();
-    println!("Temperature Converter");
+/// Absolute zero in degrees Celsius.
+pub const ABSOLUTE_ZERO_CELSIUS: f64 = -273.15;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scale {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+    Rankine,
+    Delisle,
+    Newton,
+    Reaumur,
+    Romer,
+}
+
+impl Scale {
+    pub const ALL: [Scale; 8] = [
+        Scale::Celsius,
+        Scale::Fahrenheit,
+        Scale::Kelvin,
+        Scale::Rankine,
+        Scale::Delisle,
+        Scale::Newton,
+        Scale::Reaumur,
+        Scale::Romer,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Scale::Celsius => "Celsius",
+            Scale::Fahrenheit => "Fahrenheit",
+            Scale::Kelvin => "Kelvin",
+            Scale::Rankine => "Rankine",
+            Scale::Delisle => "Delisle",
+            Scale::Newton => "Newton",
+            Scale::Reaumur => "Réaumur",
+            Scale::Romer => "Rømer",
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Scale::Celsius => "°C",
+            Scale::Fahrenheit => "°F",
+            Scale::Kelvin => "K",
+            Scale::Rankine => "°R",
+            Scale::Delisle => "°De",
+            Scale::Newton => "°N",
+            Scale::Reaumur => "°Ré",
+            Scale::Romer => "°Rø",
+        }
+    }
+
+    /// Look a scale up by name or symbol, ignoring case and a leading degree sign.
+    pub fn parse(text: &str) -> Option<Scale> {
+        let text = text.trim().trim_start_matches('°').to_lowercase();
+        let scale = match text.as_str() {
+            "c" | "celsius" => Scale::Celsius,
+            "f" | "fahrenheit" => Scale::Fahrenheit,
+            "k" | "kelvin" => Scale::Kelvin,
+            "r" | "ra" | "rankine" => Scale::Rankine,
+            "de" | "d" | "delisle" => Scale::Delisle,
+            "n" | "newton" => Scale::Newton,
+            "ré" | "re" | "réaumur" | "reaumur" => Scale::Reaumur,
+            "rø" | "ro" | "rømer" | "romer" => Scale::Romer,
+            _ => return None,
+        };
+        Some(scale)
+    }
+
+    fn to_celsius(self, value: f64) -> f64 {
+        match self {
+            Scale::Celsius => value,
+            Scale::Fahrenheit => (value - 32.0) / 1.8,
+            Scale::Kelvin => value + ABSOLUTE_ZERO_CELSIUS,
+            Scale::Rankine => value / 1.8 + ABSOLUTE_ZERO_CELSIUS,
+            Scale::Delisle => 100.0 - value * 2.0 / 3.0,
+            Scale::Newton => value * 100.0 / 33.0,
+            Scale::Reaumur => value * 1.25,
+            Scale::Romer => (value - 7.5) * 40.0 / 21.0,
+        }
+    }
+
+    fn convert_celsius(self, celsius: f64) -> f64 {
+        match self {
+            Scale::Celsius => celsius,
+            Scale::Fahrenheit => celsius * 1.8 + 32.0,
+            Scale::Kelvin => celsius - ABSOLUTE_ZERO_CELSIUS,
+            Scale::Rankine => (celsius - ABSOLUTE_ZERO_CELSIUS) * 1.8,
+            Scale::Delisle => (100.0 - celsius) * 1.5,
+            Scale::Newton => celsius * 33.0 / 100.0,
+            Scale::Reaumur => celsius * 0.8,
+            Scale::Romer => celsius * 21.0 / 40.0 + 7.5,
+        }
+    }
+}
+
+/// A temperature no colder than absolute zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Temperature {
+    value: f64,
+    scale: Scale,
+}
+
+impl Temperature {
+    pub fn new(value: f64, scale: Scale) -> Result<Self, String> {
+        if !value.is_finite() {
+            return Err(format!("{} is not a valid temperature", value));
+        }
+        // Allow for rounding in the conversion so absolute zero itself is accepted.
+        if scale.to_celsius(value) < ABSOLUTE_ZERO_CELSIUS - 1e-9 {
+            return Err(format!(
+                "{} {} is below absolute zero ({:.2} {})",
+                value,
+                scale.symbol(),
+                scale.convert_celsius(ABSOLUTE_ZERO_CELSIUS),
+                scale.symbol()
+            ));
+        }
+        Ok(Temperature { value, scale })
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn scale(&self) -> Scale {
+        self.scale
+    }
+
+    pub fn to(&self, scale: Scale) -> Temperature {
+        Temperature {
+            value: scale.convert_celsius(self.scale.to_celsius(self.value)),
+            scale,
+        }
+    }
+
+    /// Parse a temperature such as `100C`, `-40 °F` or `0 kelvin`. Without a unit the value
+    /// is taken to be in `default_scale`, if one is given.
+    pub fn parse(text: &str, default_scale: Option<Scale>) -> Result<Self, String> {
+        let re = Regex::new(r"^\s*([+-]?(?:\d+\.?\d*|\.\d+)(?:[eE][+-]?\d+)?)\s*(\S*)\s*$")
+            .expect("Failed to compile temperature regex");
+        let caps = re
+            .captures(text)
+            .ok_or_else(|| format!("\"{}\" is not a valid temperature", text.trim()))?;
+        let value = caps[1]
+            .parse::<f64>()
+            .map_err(|_| format!("\"{}\" is not a valid number", &caps[1]))?;
+        let scale = match &caps[2] {
+            "" => default_scale.ok_or_else(|| {
+                format!("Please give a unit for {}, for example {}C", value, value)
+            })?,
+            unit => Scale::parse(unit).ok_or_else(|| unknown_scale(unit))?,
+        };
+        Temperature::new(value, scale)
+    }
+}
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.2} {}", self.value, self.scale.symbol())
+    }
+}
+
+fn unknown_scale(unit: &str) -> String {
+    let symbols: Vec<&str> = Scale::ALL.iter().map(|scale| scale.symbol()).collect();
+    format!(
+        "Unknown temperature scale \"{}\". Try one of {}",
+        unit,
+        symbols.join(", ")
+    )
+}
+
+/// What the user asked for: a single conversion, or a table of every scale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Request {
+    Convert(Temperature, Scale),
+    Table(Temperature),
+}
+
+/// Parse `100C to K` (also `in` or `->`) as a conversion, or a lone temperature as a table.
+pub fn parse_request(text: &str) -> Result<Request, String> {
+    let re = Regex::new(r"(?i)^(.*?)\s+(?:to|in|->)\s+(\S+)\s*$")
+        .expect("Failed to compile conversion regex");
+    match re.captures(text) {
+        Some(caps) => {
+            let temperature = Temperature::parse(&caps[1], None)?;
+            let target = Scale::parse(&caps[2]).ok_or_else(|| unknown_scale(&caps[2]))?;
+            Ok(Request::Convert(temperature, target))
+        }
+        None => Ok(Request::Table(Temperature::parse(text, None)?)),
+    }
+}
+
+pub fn format_conversion(temperature: &Temperature, target: Scale) -> String {
+    format!("{} = {}", temperature, temperature.to(target))
+}
+
+/// The temperature in every scale, one line per scale.
+pub fn format_table(temperature: &Temperature) -> String {
+    Scale::ALL
+        .iter()
+        .map(|&scale| {
+            format!(
+                "{:<12}{:>12.2} {}",
+                scale.name(),
+                temperature.to(scale).value,
+                scale.symbol()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn launch(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    writeln!(console, "Temperature Converter");
+    let symbols: Vec<&str> = Scale::ALL.iter().map(|scale| scale.symbol()).collect();
+    writeln!(console, "Scales: {}", symbols.join(", "));
     loop {
-        println!("Enter the temperature in degrees, or press Enter to go back:");
-        let degrees = utils::read_input();
-        if degrees.trim().is_empty() {
+        writeln!(
+            console,
+            "Enter a conversion like \"100C to K\", a temperature like \"100C\" for a full table, or press Enter to go back:"
+        );
+        let input = utils::read_input(console);
+        if input.is_empty() {
             break;
         }
-        let degrees = match degrees.trim().parse::<f64>() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Please enter a valid number");
-                continue;
+
+        match parse_request(&input) {
+            Ok(Request::Convert(temperature, target)) => {
+                writeln!(console, "{}", format_conversion(&temperature, target))
             }
-        };
+            Ok(Request::Table(temperature)) => writeln!(console, "{}", format_table(&temperature)),
+            Err(message) => writeln!(console, "{}", message),
+        }
+    }
+}
 
-        println!("Enter the unit to convert to (C/F):");
-        let unit = utils::read_input();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        match unit.trim().to_lowercase().as_str() {
-            "f" => {
-                let converted = 1.8 * degrees + 32.0;
-                println!("{degrees} degrees C = {converted:.2} degrees F");
-            }
-            "c" => {
-                let converted = (degrees - 32.0) / 1.8;
-                println!("{degrees} degrees F = {converted:.2} degrees C");
-            }
-            _ => {
-                println!("Invalid unit. Try again!")
+    fn convert(value: f64, from: Scale, to: Scale) -> f64 {
+        Temperature::new(value, from).unwrap().to(to).value()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_boiling_point_in_every_scale() {
+        let expected = [100.0, 212.0, 373.15, 671.67, 0.0, 33.0, 80.0, 60.0];
+        for (scale, expected) in Scale::ALL.into_iter().zip(expected) {
+            assert_close(convert(100.0, Scale::Celsius, scale), expected);
+            assert_close(convert(expected, scale, Scale::Celsius), 100.0);
+        }
+    }
+
+    #[test]
+    fn test_round_trips() {
+        for from in Scale::ALL {
+            for to in Scale::ALL {
+                assert_close(convert(convert(36.6, from, to), to, from), 36.6);
             }
         }
+        assert_close(convert(-40.0, Scale::Fahrenheit, Scale::Celsius), -40.0);
+    }
+
+    #[test]
+    fn test_absolute_zero() {
+        assert!(Temperature::new(0.0, Scale::Kelvin).is_ok());
+        assert!(Temperature::new(-459.67, Scale::Fahrenheit).is_ok());
+        assert!(Temperature::new(-1.0, Scale::Kelvin).is_err());
+        assert!(Temperature::new(-300.0, Scale::Celsius).is_err());
+        // Delisle runs backwards: bigger numbers are colder.
+        assert!(Temperature::new(559.725, Scale::Delisle).is_ok());
+        assert!(Temperature::new(600.0, Scale::Delisle).is_err());
+    }
+
+    #[test]
+    fn test_parse_request() {
+        assert_eq!(
+            parse_request("100C to K"),
+            Ok(Request::Convert(
+                Temperature::new(100.0, Scale::Celsius).unwrap(),
+                Scale::Kelvin
+            ))
+        );
+        assert_eq!(
+            parse_request("-40 °F in rømer"),
+            Ok(Request::Convert(
+                Temperature::new(-40.0, Scale::Fahrenheit).unwrap(),
+                Scale::Romer
+            ))
+        );
+        assert_eq!(
+            parse_request("0 kelvin"),
+            Ok(Request::Table(
+                Temperature::new(0.0, Scale::Kelvin).unwrap()
+            ))
+        );
+        assert!(parse_request("100 to K").is_err());
+        assert!(parse_request("100C to X").is_err());
+        assert!(parse_request("-5K to C").is_err());
+        assert!(parse_request("hot").is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        let boiling = Temperature::new(100.0, Scale::Celsius).unwrap();
+        assert_eq!(
+            format_conversion(&boiling, Scale::Fahrenheit),
+            "100.00 °C = 212.00 °F"
+        );
+        let table = format_table(&boiling);
+        assert_eq!(table.lines().count(), 8);
+        assert!(table.contains("Kelvin            373.15 K"));
     }
 }
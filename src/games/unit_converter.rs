@@ -0,0 +1,224 @@
+pub mod registry;
+
+use super::temp_converter::{self, Scale, Temperature};
+use crate::console::Console;
+use crate::utils;
+use regex::Regex;
+use registry::Unit;
+
+/// Decimal places shown unless the user asks for something else.
+pub const DEFAULT_PRECISION: usize = 4;
+/// Most decimal places the converter will show.
+pub const MAX_PRECISION: usize = 15;
+
+/// A value together with the unit it is measured in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub unit: Unit,
+}
+
+impl Quantity {
+    /// Express the quantity in `target`, which must have the same dimension.
+    pub fn to(&self, target: &Unit) -> Result<Quantity, String> {
+        if self.unit.dimension != target.dimension {
+            return Err(format!(
+                "Cannot convert {} ({}) to {} ({})",
+                self.unit.text, self.unit.dimension, target.text, target.dimension
+            ));
+        }
+        Ok(Quantity {
+            value: self.value * self.unit.factor / target.factor,
+            unit: target.clone(),
+        })
+    }
+
+    pub fn format(&self, precision: usize) -> String {
+        format!("{} {}", format_value(self.value, precision), self.unit.text)
+    }
+}
+
+/// Fixed notation with `precision` decimals, switching to scientific notation for values too
+/// large or too small to read that way.
+pub fn format_value(value: f64, precision: usize) -> String {
+    let magnitude = value.abs();
+    if magnitude != 0.0 && !(1e-4..1e12).contains(&magnitude) {
+        format!("{:.*e}", precision, value)
+    } else {
+        format!("{:.*}", precision, value)
+    }
+}
+
+/// Parse a conversion such as `100 km/h to mph` and return the result line.
+///
+/// Plain temperature scales on both sides are handed to the temperature converter, since
+/// they need an offset as well as a factor. Registered units win over scales, so `d` is days
+/// and `N` newtons; `°De` and `°N` name the Delisle and Newton scales.
+pub fn convert(text: &str, precision: usize) -> Result<String, String> {
+    let re = Regex::new(
+        r"(?i)^\s*([+-]?(?:\d+\.?\d*|\.\d+)(?:e[+-]?\d+)?)\s*(.+?)\s+(?:to|in|->)\s+(.+?)\s*$",
+    )
+    .expect("Failed to compile conversion regex");
+    let caps = re.captures(text).ok_or_else(|| {
+        format!(
+            "\"{}\" is not a conversion. Try something like \"100 km/h to mph\"",
+            text.trim()
+        )
+    })?;
+    let value = caps[1]
+        .parse::<f64>()
+        .map_err(|_| format!("\"{}\" is not a valid number", &caps[1]))?;
+
+    if let (Some(from), Some(to)) = (temperature_scale(&caps[2]), temperature_scale(&caps[3])) {
+        let temperature = Temperature::new(value, from)?;
+        return Ok(temp_converter::format_conversion(&temperature, to));
+    }
+
+    let quantity = Quantity {
+        value,
+        unit: registry::parse_unit(&caps[2])?,
+    };
+    let converted = quantity.to(&registry::parse_unit(&caps[3])?)?;
+    Ok(format!(
+        "{} = {}",
+        quantity.format(precision),
+        converted.format(precision)
+    ))
+}
+
+/// The temperature scale `text` names, unless it is also a registered unit.
+fn temperature_scale(text: &str) -> Option<Scale> {
+    match registry::parse_unit(text) {
+        Ok(_) => None,
+        Err(_) => Scale::parse(text),
+    }
+}
+
+pub fn parse_precision(text: &str) -> Result<usize, String> {
+    text.trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|precision| *precision <= MAX_PRECISION)
+        .ok_or_else(|| {
+            format!(
+                "Precision must be a number of decimals from 0 to {}",
+                MAX_PRECISION
+            )
+        })
+}
+
+pub fn launch(console: &mut dyn Console) {
+    utils::clear_screen(console);
+    writeln!(console, "Unit Converter");
+    writeln!(console, "Units: {}", registry::symbols().join(", "));
+    writeln!(
+        console,
+        "SI prefixes (k, M, m, µ, ...) and compound units (km/h, kg*m/s^2) are supported."
+    );
+    let mut precision = DEFAULT_PRECISION;
+    loop {
+        writeln!(
+            console,
+            "Enter a conversion like \"100 km/h to mph\", \"precision <decimals>\", or press Enter to go back:"
+        );
+        let input = utils::read_input(console);
+        if input.is_empty() {
+            break;
+        }
+        if let Some(decimals) = input.strip_prefix("precision") {
+            match parse_precision(decimals) {
+                Ok(decimals) => {
+                    precision = decimals;
+                    writeln!(console, "Showing {} decimals", precision);
+                }
+                Err(message) => writeln!(console, "{}", message),
+            }
+            continue;
+        }
+        match convert(&input, precision) {
+            Ok(result) => writeln!(console, "{}", result),
+            Err(message) => writeln!(console, "{}", message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(
+            convert("100 km/h to mph", 2),
+            Ok("100.00 km/h = 62.14 mph".to_string())
+        );
+        assert_eq!(
+            convert("1 kg*m/s^2 in N", 1),
+            Ok("1.0 kg*m/s^2 = 1.0 N".to_string())
+        );
+        assert_eq!(
+            convert("1 atm to psi", 3),
+            Ok("1.000 atm = 14.696 psi".to_string())
+        );
+        assert_eq!(
+            convert("1 GiB to MB", 3),
+            Ok("1.000 GiB = 1073.742 MB".to_string())
+        );
+        assert_eq!(
+            convert("2 gal to L", 4),
+            Ok("2.0000 gal = 7.5708 L".to_string())
+        );
+        assert_eq!(
+            convert("90 min to h", 1),
+            Ok("90.0 min = 1.5 h".to_string())
+        );
+    }
+
+    #[test]
+    fn test_dimension_mismatch() {
+        assert_eq!(
+            convert("1 m to kg", 2),
+            Err("Cannot convert m (length) to kg (mass)".to_string())
+        );
+        assert!(convert("1 km/h to m/s^2", 2).is_err());
+        assert!(convert("1 furlong to m", 2).is_err());
+        assert!(convert("km to m", 2).is_err());
+        assert!(convert("1 L^9*L^9*L^9*L^9*L^9 to m", 2).is_err());
+    }
+
+    #[test]
+    fn test_temperatures_use_the_temperature_converter() {
+        assert_eq!(
+            convert("100 C to F", 2),
+            Ok("100.00 °C = 212.00 °F".to_string())
+        );
+        assert!(convert("-1 K to C", 2).is_err());
+        assert_eq!(convert("1 d to h", 0), Ok("1 d = 24 h".to_string()));
+        assert_eq!(convert("2 d to d", 0), Ok("2 d = 2 d".to_string()));
+        assert_eq!(
+            convert("0 °De to °C", 2),
+            Ok("0.00 °De = 100.00 °C".to_string())
+        );
+        assert_eq!(
+            convert("33 °N to C", 2),
+            Ok("33.00 °N = 100.00 °C".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(1234.5678, 2), "1234.57");
+        assert_eq!(format_value(0.0, 2), "0.00");
+        assert_eq!(format_value(1.5e-9, 2), "1.50e-9");
+        assert_eq!(format_value(3e15, 1), "3.0e15");
+    }
+
+    #[test]
+    fn test_precision_setting() {
+        let mut console = ScriptedConsole::new(["precision 1", "1 mi to km", "precision 99", ""]);
+        launch(&mut console);
+        assert!(console.output().contains("1.0 mi = 1.6 km"));
+        assert!(console.output().contains("Precision must be"));
+    }
+}
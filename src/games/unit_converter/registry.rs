@@ -0,0 +1,357 @@
+use std::fmt;
+
+/// Exponents of the base dimensions: length, mass, time and information.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Dimension([i8; 4]);
+
+impl Dimension {
+    pub const NONE: Dimension = Dimension([0, 0, 0, 0]);
+    pub const LENGTH: Dimension = Dimension([1, 0, 0, 0]);
+    pub const MASS: Dimension = Dimension([0, 1, 0, 0]);
+    pub const TIME: Dimension = Dimension([0, 0, 1, 0]);
+    pub const INFORMATION: Dimension = Dimension([0, 0, 0, 1]);
+    pub const AREA: Dimension = Dimension([2, 0, 0, 0]);
+    pub const VOLUME: Dimension = Dimension([3, 0, 0, 0]);
+    pub const SPEED: Dimension = Dimension([1, 0, -1, 0]);
+    pub const ACCELERATION: Dimension = Dimension([1, 0, -2, 0]);
+    pub const FORCE: Dimension = Dimension([1, 1, -2, 0]);
+    pub const PRESSURE: Dimension = Dimension([-1, 1, -2, 0]);
+    pub const ENERGY: Dimension = Dimension([2, 1, -2, 0]);
+    pub const POWER: Dimension = Dimension([2, 1, -3, 0]);
+    pub const FREQUENCY: Dimension = Dimension([0, 0, -1, 0]);
+    pub const DATA_RATE: Dimension = Dimension([0, 0, -1, 1]);
+
+    const NAMES: [(Dimension, &'static str); 15] = [
+        (Dimension::NONE, "dimensionless"),
+        (Dimension::LENGTH, "length"),
+        (Dimension::MASS, "mass"),
+        (Dimension::TIME, "time"),
+        (Dimension::INFORMATION, "data size"),
+        (Dimension::AREA, "area"),
+        (Dimension::VOLUME, "volume"),
+        (Dimension::SPEED, "speed"),
+        (Dimension::ACCELERATION, "acceleration"),
+        (Dimension::FORCE, "force"),
+        (Dimension::PRESSURE, "pressure"),
+        (Dimension::ENERGY, "energy"),
+        (Dimension::POWER, "power"),
+        (Dimension::FREQUENCY, "frequency"),
+        (Dimension::DATA_RATE, "data rate"),
+    ];
+
+    /// Exponents added with `sign`, or `None` if one leaves the range of an `i8`.
+    fn combine(self, other: Dimension, sign: i8) -> Option<Dimension> {
+        let mut exponents = self.0;
+        for (exponent, other) in exponents.iter_mut().zip(other.0) {
+            *exponent = exponent.checked_add(sign.checked_mul(other)?)?;
+        }
+        Some(Dimension(exponents))
+    }
+
+    pub fn checked_mul(self, other: Dimension) -> Option<Dimension> {
+        self.combine(other, 1)
+    }
+
+    pub fn checked_div(self, other: Dimension) -> Option<Dimension> {
+        self.combine(other, -1)
+    }
+
+    pub fn checked_pow(self, power: i8) -> Option<Dimension> {
+        let mut exponents = self.0;
+        for exponent in &mut exponents {
+            *exponent = exponent.checked_mul(power)?;
+        }
+        Some(Dimension(exponents))
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((_, name)) = Dimension::NAMES.iter().find(|(dim, _)| dim == self) {
+            return write!(f, "{}", name);
+        }
+        let parts: Vec<String> = ["m", "kg", "s", "bit"]
+            .iter()
+            .zip(self.0)
+            .filter(|(_, exponent)| *exponent != 0)
+            .map(|(symbol, exponent)| match exponent {
+                1 => symbol.to_string(),
+                _ => format!("{}^{}", symbol, exponent),
+            })
+            .collect();
+        write!(f, "{}", parts.join("*"))
+    }
+}
+
+/// Which prefixes a unit accepts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Prefixes {
+    None,
+    Si,
+    /// SI prefixes plus the binary ones (Ki, Mi, ...) used for data sizes.
+    SiAndBinary,
+}
+
+struct UnitDef {
+    symbol: &'static str,
+    names: &'static [&'static str],
+    /// Size of the unit in SI base units (metre, kilogram, second, bit).
+    factor: f64,
+    dimension: Dimension,
+    prefixes: Prefixes,
+}
+
+const fn unit(
+    symbol: &'static str,
+    names: &'static [&'static str],
+    factor: f64,
+    dimension: Dimension,
+    prefixes: Prefixes,
+) -> UnitDef {
+    UnitDef {
+        symbol,
+        names,
+        factor,
+        dimension,
+        prefixes,
+    }
+}
+
+#[rustfmt::skip]
+const UNITS: &[UnitDef] = &[
+    // Length
+    unit("m", &["meter", "meters", "metre", "metres"], 1.0, Dimension::LENGTH, Prefixes::Si),
+    unit("in", &["inch", "inches"], 0.0254, Dimension::LENGTH, Prefixes::None),
+    unit("ft", &["foot", "feet"], 0.3048, Dimension::LENGTH, Prefixes::None),
+    unit("yd", &["yard", "yards"], 0.9144, Dimension::LENGTH, Prefixes::None),
+    unit("mi", &["mile", "miles"], 1609.344, Dimension::LENGTH, Prefixes::None),
+    unit("nmi", &["nautical mile", "nautical miles"], 1852.0, Dimension::LENGTH, Prefixes::None),
+    // Mass
+    unit("g", &["gram", "grams"], 1e-3, Dimension::MASS, Prefixes::Si),
+    unit("t", &["tonne", "tonnes"], 1000.0, Dimension::MASS, Prefixes::None),
+    unit("lb", &["pound", "pounds", "lbs"], 0.45359237, Dimension::MASS, Prefixes::None),
+    unit("oz", &["ounce", "ounces"], 0.028349523125, Dimension::MASS, Prefixes::None),
+    unit("st", &["stone", "stones"], 6.35029318, Dimension::MASS, Prefixes::None),
+    // Time
+    unit("s", &["second", "seconds", "sec"], 1.0, Dimension::TIME, Prefixes::Si),
+    unit("min", &["minute", "minutes"], 60.0, Dimension::TIME, Prefixes::None),
+    unit("h", &["hour", "hours", "hr"], 3600.0, Dimension::TIME, Prefixes::None),
+    unit("d", &["day", "days"], 86400.0, Dimension::TIME, Prefixes::None),
+    unit("wk", &["week", "weeks"], 604800.0, Dimension::TIME, Prefixes::None),
+    unit("yr", &["year", "years"], 31557600.0, Dimension::TIME, Prefixes::None),
+    // Volume
+    unit("L", &["l", "liter", "liters", "litre", "litres"], 1e-3, Dimension::VOLUME, Prefixes::Si),
+    unit("gal", &["gallon", "gallons"], 3.785411784e-3, Dimension::VOLUME, Prefixes::None),
+    unit("qt", &["quart", "quarts"], 9.46352946e-4, Dimension::VOLUME, Prefixes::None),
+    unit("pt", &["pint", "pints"], 4.73176473e-4, Dimension::VOLUME, Prefixes::None),
+    unit("cup", &["cups"], 2.365882365e-4, Dimension::VOLUME, Prefixes::None),
+    unit("floz", &["fluid ounce", "fluid ounces"], 2.95735295625e-5, Dimension::VOLUME, Prefixes::None),
+    // Speed
+    unit("mph", &["miles per hour"], 0.44704, Dimension::SPEED, Prefixes::None),
+    unit("kn", &["knot", "knots"], 1852.0 / 3600.0, Dimension::SPEED, Prefixes::None),
+    // Data size
+    unit("bit", &["bits"], 1.0, Dimension::INFORMATION, Prefixes::SiAndBinary),
+    unit("B", &["byte", "bytes"], 8.0, Dimension::INFORMATION, Prefixes::SiAndBinary),
+    // Pressure
+    unit("Pa", &["pascal", "pascals"], 1.0, Dimension::PRESSURE, Prefixes::Si),
+    unit("bar", &["bars"], 1e5, Dimension::PRESSURE, Prefixes::Si),
+    unit("atm", &["atmosphere", "atmospheres"], 101325.0, Dimension::PRESSURE, Prefixes::None),
+    unit("psi", &[], 6894.757293168361, Dimension::PRESSURE, Prefixes::None),
+    unit("mmHg", &[], 133.322387415, Dimension::PRESSURE, Prefixes::None),
+    unit("Torr", &["torr"], 101325.0 / 760.0, Dimension::PRESSURE, Prefixes::None),
+    // Derived SI units, so compound units have something to convert to
+    unit("N", &["newton", "newtons"], 1.0, Dimension::FORCE, Prefixes::Si),
+    unit("J", &["joule", "joules"], 1.0, Dimension::ENERGY, Prefixes::Si),
+    unit("W", &["watt", "watts"], 1.0, Dimension::POWER, Prefixes::Si),
+    unit("Hz", &["hertz"], 1.0, Dimension::FREQUENCY, Prefixes::Si),
+];
+
+#[rustfmt::skip]
+const SI_PREFIXES: &[(&str, &str, f64)] = &[
+    ("Q", "quetta", 1e30), ("R", "ronna", 1e27), ("Y", "yotta", 1e24), ("Z", "zetta", 1e21),
+    ("E", "exa", 1e18), ("P", "peta", 1e15), ("T", "tera", 1e12), ("G", "giga", 1e9),
+    ("M", "mega", 1e6), ("k", "kilo", 1e3), ("h", "hecto", 1e2), ("da", "deca", 1e1),
+    ("d", "deci", 1e-1), ("c", "centi", 1e-2), ("m", "milli", 1e-3), ("µ", "micro", 1e-6),
+    ("u", "micro", 1e-6), ("n", "nano", 1e-9), ("p", "pico", 1e-12), ("f", "femto", 1e-15),
+    ("a", "atto", 1e-18), ("z", "zepto", 1e-21), ("y", "yocto", 1e-24), ("r", "ronto", 1e-27),
+    ("q", "quecto", 1e-30),
+];
+
+#[rustfmt::skip]
+const BINARY_PREFIXES: &[(&str, &str, f64)] = &[
+    ("Ki", "kibi", 1024.0), ("Mi", "mebi", 1048576.0), ("Gi", "gibi", 1073741824.0),
+    ("Ti", "tebi", 1099511627776.0), ("Pi", "pebi", 1125899906842624.0),
+    ("Ei", "exbi", 1152921504606846976.0),
+];
+
+/// A unit, possibly compound, expressed in SI base units.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unit {
+    pub text: String,
+    pub factor: f64,
+    pub dimension: Dimension,
+}
+
+/// Look up a single unit with an optional prefix, such as `km`, `MiB` or `kilometres`.
+fn lookup(text: &str) -> Option<(f64, Dimension)> {
+    // Whole symbols and names win over prefixed ones, so `min` is minutes, not milli-inches.
+    let lower = text.to_lowercase();
+    if let Some(def) = UNITS
+        .iter()
+        .find(|def| def.symbol == text || def.names.contains(&lower.as_str()))
+    {
+        return Some((def.factor, def.dimension));
+    }
+
+    UNITS
+        .iter()
+        .filter(|def| def.prefixes != Prefixes::None)
+        .find_map(|def| {
+            let binary: &[(&str, &str, f64)] = match def.prefixes {
+                Prefixes::SiAndBinary => BINARY_PREFIXES,
+                _ => &[],
+            };
+            SI_PREFIXES
+                .iter()
+                .chain(binary)
+                .find_map(|&(symbol, name, scale)| {
+                    let by_symbol = text
+                        .strip_prefix(symbol)
+                        .is_some_and(|rest| rest == def.symbol);
+                    let by_name = lower
+                        .strip_prefix(name)
+                        .is_some_and(|rest| def.names.contains(&rest));
+                    (by_symbol || by_name).then_some(scale * def.factor)
+                })
+                .map(|factor| (factor, def.dimension))
+        })
+}
+
+fn too_large(unit: &str) -> String {
+    format!("\"{}\" has exponents that are too large", unit.trim())
+}
+
+/// Parse a unit term such as `m`, `s^2` or `kg^-1`.
+fn parse_term(term: &str) -> Result<(f64, Dimension), String> {
+    let (name, power) = match term.split_once('^') {
+        Some((name, power)) => {
+            let power = power
+                .trim()
+                .parse::<i8>()
+                .ok()
+                .filter(|power| (-9..=9).contains(power))
+                .ok_or_else(|| format!("\"{}\" has an invalid exponent", term))?;
+            (name.trim(), power)
+        }
+        None => (term.trim(), 1),
+    };
+    let (factor, dimension) = lookup(name).ok_or_else(|| format!("Unknown unit \"{}\"", name))?;
+    let dimension = dimension
+        .checked_pow(power)
+        .ok_or_else(|| too_large(term))?;
+    Ok((factor.powi(power as i32), dimension))
+}
+
+/// Parse a unit expression: terms joined by `*` (or `·`), with at most one `/` after which
+/// every term divides. Examples: `km/h`, `kg*m/s^2`, `MiB/s`.
+pub fn parse_unit(text: &str) -> Result<Unit, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Missing unit".to_string());
+    }
+    let (numerator, denominator) = match text.split_once('/') {
+        Some((numerator, denominator)) => (numerator, Some(denominator)),
+        None => (text, None),
+    };
+
+    let mut factor = 1.0;
+    let mut dimension = Dimension::NONE;
+    for term in numerator.split(['*', '·']) {
+        let (term_factor, term_dimension) = parse_term(term)?;
+        factor *= term_factor;
+        dimension = dimension
+            .checked_mul(term_dimension)
+            .ok_or_else(|| too_large(text))?;
+    }
+    if let Some(denominator) = denominator {
+        for term in denominator.split(['*', '·']) {
+            let (term_factor, term_dimension) = parse_term(term)?;
+            factor /= term_factor;
+            dimension = dimension
+                .checked_div(term_dimension)
+                .ok_or_else(|| too_large(text))?;
+        }
+    }
+    Ok(Unit {
+        text: text.to_string(),
+        factor,
+        dimension,
+    })
+}
+
+/// Symbols of every registered unit, for help text.
+pub fn symbols() -> Vec<&'static str> {
+    UNITS.iter().map(|def| def.symbol).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            ((actual - expected) / expected).abs() < 1e-12,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_simple_and_prefixed_units() {
+        assert_eq!(parse_unit("m").unwrap().dimension, Dimension::LENGTH);
+        assert_close(parse_unit("km").unwrap().factor, 1000.0);
+        assert_close(parse_unit("kilometres").unwrap().factor, 1000.0);
+        assert_close(parse_unit("mm").unwrap().factor, 1e-3);
+        assert_close(parse_unit("µs").unwrap().factor, 1e-6);
+        assert_close(parse_unit("kg").unwrap().factor, 1.0);
+        assert_close(parse_unit("min").unwrap().factor, 60.0);
+        assert_close(parse_unit("mL").unwrap().factor, 1e-6);
+        assert_close(parse_unit("MiB").unwrap().factor, 8.0 * 1048576.0);
+        assert_close(parse_unit("kbit").unwrap().factor, 1000.0);
+        assert!(parse_unit("Kim").is_err());
+        assert!(parse_unit("kft").is_err());
+        assert!(parse_unit("parsec").is_err());
+    }
+
+    #[test]
+    fn test_compound_units() {
+        let speed = parse_unit("km/h").unwrap();
+        assert_eq!(speed.dimension, Dimension::SPEED);
+        assert_close(speed.factor, 1000.0 / 3600.0);
+
+        let force = parse_unit("kg*m/s^2").unwrap();
+        assert_eq!(force.dimension, Dimension::FORCE);
+        assert_close(force.factor, 1.0);
+
+        assert_eq!(parse_unit("m^3").unwrap().dimension, Dimension::VOLUME);
+        assert_eq!(parse_unit("s^-1").unwrap().dimension, Dimension::FREQUENCY);
+        assert_eq!(parse_unit("MB/s").unwrap().dimension, Dimension::DATA_RATE);
+        assert!(parse_unit("m^x").is_err());
+        assert!(parse_unit("m/").is_err());
+        assert_eq!(
+            parse_unit("L^9*L^9*L^9*L^9*L^9"),
+            Err("\"L^9*L^9*L^9*L^9*L^9\" has exponents that are too large".to_string())
+        );
+        assert!(parse_unit("s/L^9*L^9*L^9*L^9*L^9").is_err());
+    }
+
+    #[test]
+    fn test_dimension_display() {
+        assert_eq!(Dimension::PRESSURE.to_string(), "pressure");
+        let dimension = Dimension::LENGTH.checked_mul(Dimension::MASS).unwrap();
+        assert_eq!(dimension.to_string(), "m*kg");
+        assert_eq!(
+            Dimension::MASS.checked_pow(-2).unwrap().to_string(),
+            "kg^-2"
+        );
+    }
+}
@@ -1,37 +1,88 @@
+pub mod cli;
+pub mod console;
 pub mod dsa;
 pub mod exercises;
 pub mod games;
+pub mod menu;
+pub mod random;
 pub mod utils;
 
-pub fn launch() {
-    loop {
-        utils::clear_screen();
-        // check if user wants to play a game or solve an exercise
-        println!("Do you want to play a game or solve an exercise?");
-        println!("1. Play a game");
-        println!("2. Solve an exercise");
-        println!("3. Data structures and algorithms");
-        println!("0. Exit");
-        let game_or_exercise = utils::read_input();
-
-        let game_or_exercise = match game_or_exercise.trim().parse::<u32>() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Please enter a number");
-                continue;
-            }
-        };
-
-        match game_or_exercise {
-            1 => games::launch(),
-            2 => exercises::launch(),
-            3 => dsa::launch(),
-            0 => break,
-            _ => {
-                println!("Invalid selection");
-                continue;
-            }
-        }
+use console::Console;
+use menu::Menu;
+
+pub fn launch(console: &mut dyn Console) {
+    writeln!(
+        console,
+        "Session seed: {0} (replay with --seed {0} or {1}={0})",
+        random::current_seed(),
+        random::SEED_ENV_VAR
+    );
+    Menu::new("Main menu")
+        .submenu("Play a game", games::menu())
+        .submenu("Solve an exercise", exercises::menu())
+        .submenu("Data structures and algorithms", dsa::menu())
+        .run(console);
+    writeln!(console, "Thanks for playing! See you next time!")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::ScriptedConsole;
+
+    #[test]
+    fn test_exit_immediately() {
+        let mut console = ScriptedConsole::new(["0"]);
+        launch(&mut console);
+        assert!(console
+            .output()
+            .ends_with("Thanks for playing! See you next time!\n"));
+    }
+
+    #[test]
+    fn test_end_of_input_exits() {
+        let mut console = ScriptedConsole::new(["abc"]);
+        launch(&mut console);
+        assert!(console.output().contains("Invalid selection \"abc\""));
+        assert!(console
+            .output()
+            .ends_with("Thanks for playing! See you next time!\n"));
+    }
+
+    #[test]
+    fn test_temperature_converter_session() {
+        let mut console = ScriptedConsole::new(["1", "2", "100C to F", "", "", "0", "0"]);
+        launch(&mut console);
+        assert!(console.output().contains("Main menu > Games\n"));
+        assert!(console.output().contains("100.00 °C = 212.00 °F"));
+        assert_eq!(console.remaining_input(), 0);
+    }
+
+    #[test]
+    fn test_pig_latin_session() {
+        let mut console =
+            ScriptedConsole::new(["2", "2", "1", "hello apple", "", "", "0", "0", "0"]);
+        launch(&mut console);
+        assert!(console.output().contains("Pig Latin: ellohay applehay"));
+        assert_eq!(console.remaining_input(), 0);
+    }
+
+    #[test]
+    fn test_stack_session() {
+        let mut console =
+            ScriptedConsole::new(["3", "1", "1", "a,b,c", "", "2", "", "0", "", "", "0", "0"]);
+        launch(&mut console);
+        assert!(console.output().contains("Popped value: c"));
+        assert_eq!(console.remaining_input(), 0);
+    }
+
+    #[test]
+    fn test_quit_from_nested_menu() {
+        let mut console = ScriptedConsole::new(["3", "q", "0"]);
+        launch(&mut console);
+        assert_eq!(console.remaining_input(), 1);
+        assert!(console
+            .output()
+            .ends_with("Thanks for playing! See you next time!\n"));
     }
-    println!("Thanks for playing! See you next time!")
 }
@@ -1,5 +1,20 @@
-use guessing_game::launch;
+use guessing_game::console::StdConsole;
+use guessing_game::{cli, launch};
+use std::process;
 
 fn main() {
-    launch();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut console = StdConsole::new();
+    let result = cli::apply_seed(&args).and_then(|args| {
+        if args.is_empty() {
+            launch(&mut console);
+            Ok(())
+        } else {
+            cli::run(&args, &mut console)
+        }
+    });
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(err.exit_code());
+    }
 }
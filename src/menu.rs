@@ -0,0 +1,194 @@
+use crate::console::Console;
+use crate::utils;
+
+type Handler<'a> = Box<dyn FnMut(&mut dyn Console) + 'a>;
+
+enum Action<'a> {
+    Run(Handler<'a>),
+    Submenu(Menu<'a>),
+}
+
+struct Entry<'a> {
+    label: String,
+    action: Action<'a>,
+}
+
+/// How a menu was left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuExit {
+    /// The user went back to the parent menu.
+    Back,
+    /// The user asked to quit, or the input was exhausted. Parent menus close as well.
+    Quit,
+}
+
+/// A numbered menu. Entries are listed from 1 in registration order; `0` or `b` goes back,
+/// `q` quits every menu up to the root. The path of menu titles is shown above the options.
+pub struct Menu<'a> {
+    title: String,
+    entries: Vec<Entry<'a>>,
+}
+
+impl<'a> Menu<'a> {
+    pub fn new(title: &str) -> Self {
+        Menu {
+            title: title.to_string(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Register an entry that runs `handler` when selected, then returns to this menu.
+    pub fn entry<F>(mut self, label: &str, handler: F) -> Self
+    where
+        F: FnMut(&mut dyn Console) + 'a,
+    {
+        self.entries.push(Entry {
+            label: label.to_string(),
+            action: Action::Run(Box::new(handler)),
+        });
+        self
+    }
+
+    /// Register an entry that opens `menu` as a nested menu.
+    pub fn submenu(mut self, label: &str, menu: Menu<'a>) -> Self {
+        self.entries.push(Entry {
+            label: label.to_string(),
+            action: Action::Submenu(menu),
+        });
+        self
+    }
+
+    /// Show the menu until the user goes back or quits.
+    pub fn run(&mut self, console: &mut dyn Console) -> MenuExit {
+        self.run_at(console, &mut Vec::new())
+    }
+
+    fn run_at(&mut self, console: &mut dyn Console, path: &mut Vec<String>) -> MenuExit {
+        path.push(self.title.clone());
+        let back_label = if path.len() == 1 { "Exit" } else { "Back" };
+        let mut notice: Option<String> = None;
+
+        let exit = loop {
+            utils::clear_screen(console);
+            writeln!(console, "{}", path.join(" > "));
+            writeln!(console, "{}", "=".repeat(20));
+            for (i, entry) in self.entries.iter().enumerate() {
+                writeln!(console, "{}. {}", i + 1, entry.label);
+            }
+            writeln!(console, "0. {}", back_label);
+            writeln!(console, "q. Quit");
+            if let Some(message) = notice.take() {
+                writeln!(console, "{}", message);
+            }
+
+            let Some(selection) = console.read_line() else {
+                break MenuExit::Quit;
+            };
+            let selection = selection.trim().to_lowercase();
+            match selection.as_str() {
+                "0" | "b" => break MenuExit::Back,
+                "q" => break MenuExit::Quit,
+                _ => {}
+            }
+
+            let index = match selection.parse::<usize>() {
+                Ok(num) if (1..=self.entries.len()).contains(&num) => num - 1,
+                _ => {
+                    notice = Some(format!(
+                        "Invalid selection \"{}\". Enter a number from 0 to {}, or q to quit.",
+                        selection,
+                        self.entries.len()
+                    ));
+                    continue;
+                }
+            };
+
+            match &mut self.entries[index].action {
+                Action::Run(handler) => {
+                    handler(console);
+                    writeln!(console, "Press Enter to return to {}...", self.title);
+                    if console.read_line().is_none() {
+                        break MenuExit::Quit;
+                    }
+                }
+                Action::Submenu(menu) => {
+                    if menu.run_at(console, path) == MenuExit::Quit {
+                        break MenuExit::Quit;
+                    }
+                }
+            }
+        };
+
+        path.pop();
+        exit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScriptedConsole;
+
+    fn sample_menu<'a>(log: &'a std::cell::RefCell<Vec<&'static str>>) -> Menu<'a> {
+        Menu::new("Main")
+            .entry("Hello", |console| {
+                writeln!(console, "hello!");
+                log.borrow_mut().push("hello");
+            })
+            .submenu(
+                "More",
+                Menu::new("More").entry("World", |console| {
+                    writeln!(console, "world!");
+                    log.borrow_mut().push("world");
+                }),
+            )
+    }
+
+    #[test]
+    fn test_run_entry_and_exit() {
+        let log = std::cell::RefCell::new(Vec::new());
+        let mut console = ScriptedConsole::new(["1", "", "0"]);
+        assert_eq!(sample_menu(&log).run(&mut console), MenuExit::Back);
+        assert_eq!(*log.borrow(), vec!["hello"]);
+        assert!(console.output().contains("hello!"));
+        assert_eq!(console.remaining_input(), 0);
+    }
+
+    #[test]
+    fn test_submenu_back_and_breadcrumbs() {
+        let log = std::cell::RefCell::new(Vec::new());
+        let mut console = ScriptedConsole::new(["2", "1", "", "b", "1", "", "0"]);
+        assert_eq!(sample_menu(&log).run(&mut console), MenuExit::Back);
+        assert_eq!(*log.borrow(), vec!["world", "hello"]);
+        assert!(console.output().contains("Main > More\n"));
+        assert!(console
+            .output()
+            .contains("Press Enter to return to More..."));
+    }
+
+    #[test]
+    fn test_quit_from_submenu() {
+        let log = std::cell::RefCell::new(Vec::new());
+        let mut console = ScriptedConsole::new(["2", "Q", "1"]);
+        assert_eq!(sample_menu(&log).run(&mut console), MenuExit::Quit);
+        assert!(log.borrow().is_empty());
+        assert_eq!(console.remaining_input(), 1);
+    }
+
+    #[test]
+    fn test_invalid_selection() {
+        let log = std::cell::RefCell::new(Vec::new());
+        let mut console = ScriptedConsole::new(["abc", "7", "0"]);
+        assert_eq!(sample_menu(&log).run(&mut console), MenuExit::Back);
+        assert!(console.output().contains("Invalid selection \"abc\""));
+        assert!(console.output().contains("Invalid selection \"7\""));
+        assert!(log.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_end_of_input_quits() {
+        let log = std::cell::RefCell::new(Vec::new());
+        let mut console = ScriptedConsole::new(["2"]);
+        assert_eq!(sample_menu(&log).run(&mut console), MenuExit::Quit);
+    }
+}
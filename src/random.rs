@@ -0,0 +1,67 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::cell::{Cell, RefCell};
+
+/// Environment variable read for the session seed when no `--seed` flag is given.
+pub const SEED_ENV_VAR: &str = "GUESSING_GAME_SEED";
+
+thread_local! {
+    static SEED: Cell<u64> = Cell::new(rand::random());
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::seed_from_u64(current_seed()));
+}
+
+/// Restart the shared random number generator from `seed`.
+pub fn seed(seed: u64) {
+    SEED.with(|cell| cell.set(seed));
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+/// The seed the shared generator was last started from. Replaying a session with this seed
+/// gives the same random numbers.
+pub fn current_seed() -> u64 {
+    SEED.with(Cell::get)
+}
+
+/// Read a seed from `SEED_ENV_VAR`. Returns an error message if it is set but not a number.
+pub fn seed_from_env() -> Result<Option<u64>, String> {
+    match std::env::var(SEED_ENV_VAR) {
+        Ok(value) => parse_seed(&value).map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+pub fn parse_seed(value: &str) -> Result<u64, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("\"{}\" is not a valid seed", value))
+}
+
+/// Run `f` with the shared random number generator. Every random feature draws from it, so a
+/// whole session is reproducible from its seed.
+pub fn with_rng<T>(f: impl FnOnce(&mut StdRng) -> T) -> T {
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_same_seed_same_numbers() {
+        seed(42);
+        let first: Vec<u32> = with_rng(|rng| (0..5).map(|_| rng.gen_range(0..1000)).collect());
+        seed(42);
+        let second: Vec<u32> = with_rng(|rng| (0..5).map(|_| rng.gen_range(0..1000)).collect());
+        assert_eq!(first, second);
+        assert_eq!(current_seed(), 42);
+    }
+
+    #[test]
+    fn test_parse_seed() {
+        assert_eq!(parse_seed(" 123 "), Ok(123));
+        assert!(parse_seed("-1").is_err());
+        assert!(parse_seed("abc").is_err());
+    }
+}
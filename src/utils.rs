@@ -1,18 +1,14 @@
-use std::io;
-use std::io::Write;
-use termion::clear;
+use crate::console::Console;
 
 /// Clear the screen
-pub fn clear_screen() {
-    print!("{}", clear::All);
-    io::stdout().flush().expect("Failed to flush stdout");
+pub fn clear_screen(console: &mut dyn Console) {
+    console.clear();
 }
 
-/// Read input from the user
-pub fn read_input() -> String {
-    let mut input = String::new();
-    io::stdin()
-        .read_line(&mut input)
-        .expect("Failed to read line");
-    input.trim().to_string()
+/// Read input from the user. Returns an empty string once the input is exhausted.
+pub fn read_input(console: &mut dyn Console) -> String {
+    console
+        .read_line()
+        .map(|input| input.trim().to_string())
+        .unwrap_or_default()
 }